@@ -1,8 +1,8 @@
 use anyhow::Error;
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
 use std::collections::VecDeque;
-use utils::grid::Grid;
+use utils::grid::search::bfs;
+use utils::grid::{Grid, Point};
 
 fn main() {
     let input = include_str!("../../input/day10/input.txt");
@@ -12,48 +12,33 @@ fn main() {
 }
 
 fn parse_input<'a>(src: &'a str) -> Grid<i32> {
-    let vs = src
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| c.to_string().parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-        })
-        .collect::<Vec<Vec<i32>>>();
-    Grid::new(vs)
+    Grid::parse_with(src, |c| c.to_string().parse::<i32>()).expect("failed to parse grid")
 }
 
-fn walk_trail(start: (i32, i32), tm: &Grid<i32>) -> (i32, i32) {
-    let dirs = vec![(0, -1), (1, 0), (0, 1), (-1, 0)];
-    let mut q: VecDeque<(i32, i32)> = VecDeque::new();
-    let mut visited: FxHashSet<(i32, i32)> = FxHashSet::default();
+fn trail_score(start: (i32, i32), tm: &Grid<i32>) -> i32 {
+    let dist = bfs(tm, start.into(), |a, b| *b == *a + 1);
+    dist.reached().filter(|(p, _)| tm[*p] == 9).count() as i32
+}
 
+fn trail_rating(start: (i32, i32), tm: &Grid<i32>) -> i32 {
+    let mut q: VecDeque<(i32, i32)> = VecDeque::new();
     q.push_back((start.0, start.1));
 
     let mut rating = 0;
-    let mut score = 0;
 
     while let Some(pt) = q.pop_front() {
         let cur = *tm.at_unsafe(pt.0, pt.1);
 
         if cur == 9 {
-            if !visited.contains(&(pt.0, pt.1)) {
-                score += 1;
-                visited.insert((pt.0, pt.1));
-            }
             rating += 1;
         } else {
-            for d in &dirs {
-                if tm.in_bounds(pt.0 + d.0, pt.1 + d.1)
-                    && *tm.at_unsafe(pt.0 + d.0, pt.1 + d.1) == cur + 1
-                {
-                    q.push_back((pt.0 + d.0, pt.1 + d.1));
-                }
+            for (np, _) in tm.neighbors_where(Point::from(pt), |v| *v == cur + 1) {
+                q.push_back(np.into());
             }
         }
     }
 
-    (score, rating)
+    rating
 }
 
 fn part1(src: &str) -> Result<i32, Error> {
@@ -66,7 +51,7 @@ fn part1(src: &str) -> Result<i32, Error> {
 
     Ok(trailheads
         .iter()
-        .fold(0, |acc, start| acc + walk_trail(*start, &trails).0))
+        .fold(0, |acc, start| acc + trail_score(*start, &trails)))
 }
 
 fn part2(src: &str) -> Result<i32, Error> {
@@ -79,7 +64,7 @@ fn part2(src: &str) -> Result<i32, Error> {
 
     Ok(trailheads
         .iter()
-        .fold(0, |acc, start| acc + walk_trail(*start, &trails).1))
+        .fold(0, |acc, start| acc + trail_rating(*start, &trails)))
 }
 
 #[cfg(test)]
@@ -2,7 +2,8 @@ use anyhow::Error;
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::VecDeque;
-use utils::grid::Grid;
+use utils::grid::search::components;
+use utils::grid::{Grid, Point};
 
 fn main() {
     let input = include_str!("../../input/day12/input.txt");
@@ -12,58 +13,31 @@ fn main() {
 }
 
 fn parse_input<'a>(src: &'a str) -> Grid<char> {
-    let vs = src
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    Grid::new(vs)
+    Grid::parse(src).expect("failed to parse grid")
 }
 
 fn find_regions(grid: &Grid<char>) -> i64 {
-    let dirs: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-    let mut visited: FxHashSet<(i32, i32)> = FxHashSet::default();
-    let mut start: (i32, i32) = (0, 0);
-    let mut q: VecDeque<(i32, i32)> = VecDeque::new();
-
-    let mut cost: i64 = 0;
-    while visited.len() < grid.size() {
-        for (x, y) in grid.iter_points() {
-            if !visited.contains(&(x, y)) {
-                start = (x, y);
-                break;
-            }
-        }
-        q.push_front(start);
-        visited.insert((start.0, start.1));
-        let plant = grid.at_unsafe(start.0, start.1);
-
-        let mut area: i64 = 1;
-        let mut perimeter: i64 = 0;
-        let mut vv: Vec<(i32, i32)> = Vec::new();
-
-        while let Some((x, y)) = q.pop_front() {
-            for d in dirs.iter() {
-                if !visited.contains(&(x + d.0, y + d.1))
-                    && grid.in_bounds(x + d.0, y + d.1)
-                    && grid.at_unsafe(x + d.0, y + d.1) == plant
-                {
-                    perimeter += 1;
-                    q.push_front((x + d.0, y + d.1));
-                    vv.push((x, y));
-                }
-            }
-            if !visited.contains(&(x, y)) {
-                area += 1;
-                visited.insert((x, y));
-            }
-        }
-        cost += area * ((4 * area) - (2 * perimeter));
+    let regions = components(grid, |a, b| a == b);
+    let mut perimeter: Vec<i64> = vec![0; regions.len()];
+
+    for p in grid.iter_points().map(Point::from) {
+        let id = regions.ids[p];
+        let same = grid
+            .neighbors4(p)
+            .filter(|(np, _)| regions.ids[*np] == id)
+            .count();
+        perimeter[id] += 4 - same as i64;
     }
-    cost
+
+    regions
+        .sizes
+        .iter()
+        .zip(perimeter)
+        .map(|(area, perimeter)| *area as i64 * perimeter)
+        .sum()
 }
 
 fn find_regions2(grid: &Grid<char>) -> i64 {
-    let dirs: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
     let mut visited: FxHashSet<(i32, i32)> = FxHashSet::default();
     let mut start: (i32, i32) = (0, 0);
     let mut q: VecDeque<(i32, i32)> = VecDeque::new();
@@ -85,12 +59,10 @@ fn find_regions2(grid: &Grid<char>) -> i64 {
         let mut region: FxHashSet<(i32, i32)> = FxHashSet::default();
 
         while let Some((x, y)) = q.pop_front() {
-            for d in dirs.iter() {
-                if !region.contains(&(x + d.0, y + d.1))
-                    && grid.in_bounds(x + d.0, y + d.1)
-                    && grid.at_unsafe(x + d.0, y + d.1) == plant
-                {
-                    q.push_front((x + d.0, y + d.1));
+            for (np, _) in grid.neighbors_where(Point::new(x, y), |c| c == plant) {
+                let np: (i32, i32) = np.into();
+                if !region.contains(&np) {
+                    q.push_front(np);
                 }
             }
             if !region.contains(&(x, y)) {
@@ -1,5 +1,6 @@
 use anyhow::Error;
 use nalgebra::*;
+use tinyparse::*;
 
 fn main() {
     let input = include_str!("../../input/day13/input.txt");
@@ -8,42 +9,18 @@ fn main() {
     println!("part 2: {}", part2(input).unwrap());
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Parse)]
 struct Machine {
+    #[parse("Button A: X+{a.0}, Y+{a.1}\n")]
     a: (i64, i64),
+    #[parse("Button B: X+{b.0}, Y+{b.1}\n")]
     b: (i64, i64),
+    #[parse("Prize: X={p.0}, Y={p.1}")]
     p: (i64, i64),
 }
 
 fn parse_input<'a>(src: &'a str) -> Vec<Machine> {
-    let ms = src.split("\n\n").collect::<Vec<_>>();
-    let mut machines: Vec<Machine> = Vec::new();
-    let ms = ms
-        .into_iter()
-        .map(|m| m.split("\n").collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    for m in ms {
-        let a = m[0].split_once(",").unwrap();
-        let a = (
-            a.0[12..].parse().expect("parse"),
-            a.1[3..].parse().expect("parse"),
-        );
-
-        let b = m[1].split_once(",").unwrap();
-        let b = (
-            b.0[12..].parse().expect("parse"),
-            b.1[3..].parse().expect("parse"),
-        );
-
-        let p = m[2].split_once(",").unwrap();
-        let p = (
-            p.0[9..].parse().expect("parse"),
-            p.1[3..].parse().expect("parse"),
-        );
-        machines.push(Machine { a, b, p });
-    }
-
+    let (_, machines) = run(blocks(Machine::parse), src).expect("failed to parse input");
     machines
 }
 
@@ -1,8 +1,8 @@
 use anyhow::Error;
-use rustc_hash::{FxHashMap, FxHashSet};
-use std::collections::VecDeque;
-use utils::grid::Grid;
-use utils::nums;
+use rustc_hash::FxHashMap;
+use tinyparse::*;
+use utils::grid::search::components;
+use utils::grid::{Grid, Point};
 
 fn main() {
     let input = include_str!("../../input/day14/input.txt");
@@ -11,7 +11,8 @@ fn main() {
     println!("part 2: {}", part2(input).unwrap());
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Parse)]
+#[parse("p={x},{y} v={vx},{vy}")]
 struct Robot {
     x: i64,
     y: i64,
@@ -20,23 +21,9 @@ struct Robot {
 }
 
 fn parse_input<'a>(src: &'a str) -> Vec<Robot> {
-    src.lines()
-        .map(|l| {
-            let (a, b) = l.split_once(" ").unwrap();
-            let [x, y] = &nums(a)[..] else {
-                panic!("failed to parse")
-            };
-            let [vx, vy] = &nums(b)[..] else {
-                panic!("failed to parse")
-            };
-            Robot {
-                x: *x,
-                y: *y,
-                vx: *vx,
-                vy: *vy,
-            }
-        })
-        .collect::<Vec<Robot>>()
+    let robots = all_consuming(terminated(sep_by1(Robot::parse, line_ending), ws));
+    let (_, robots) = run(robots, src).expect("failed to parse input");
+    robots
 }
 
 fn part1(src: &str) -> Result<i64, Error> {
@@ -71,47 +58,10 @@ fn part1(src: &str) -> Result<i64, Error> {
 }
 
 fn connected(g: &Grid<char>) -> bool {
-    let mut seen: FxHashSet<(i32, i32)> = FxHashSet::default();
-    let dirs: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-
-    let mut max_connect = 0;
-    for y in 0..g.height {
-        for x in 0..g.width {
-            if !seen.contains(&(x, y)) && *g.at_unsafe(x, y) == '#' {
-                let mut connected = 0;
-                let mut q: VecDeque<(i32, i32)> = VecDeque::new();
-                q.push_back((x, y));
-
-                while let Some(p) = q.pop_front() {
-                    seen.insert(p);
-                    if *g.at_unsafe(p.0, p.1) != '#' {
-                        if connected > max_connect {
-                            max_connect = connected;
-                            connected = 0;
-                        }
-                        continue;
-                    }
-
-                    connected += 1;
-                    if connected > 100 {
-                        return true;
-                    }
-                    for d in dirs {
-                        if !seen.contains(&(p.0 + d.0, p.1 + d.1))
-                            && g.in_bounds(p.0 + d.0, p.1 + d.1)
-                        {
-                            if *g.at_unsafe(p.0 + d.0, p.1 + d.1) == '#' {
-                                q.push_back((p.0 + d.0, p.1 + d.1));
-                            }
-                        }
-                    }
-                }
-            } else {
-                seen.insert((x, y));
-            }
-        }
-    }
-    false
+    let regions = components(g, |a, b| a == b);
+    g.iter_points()
+        .map(Point::from)
+        .any(|p| g[p] == '#' && regions.sizes[regions.ids[p]] > 100)
 }
 
 fn part2(src: &str) -> Result<i64, Error> {
@@ -2,8 +2,8 @@ use anyhow::Error;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::VecDeque;
 use std::ops::Range;
-use utils::grid::Grid;
-use utils::nums;
+use utils::grid::{Dir4, Grid};
+use utils::input::sections_n;
 
 fn main() {
     let input = include_str!("../../input/day15/input.txt");
@@ -13,12 +13,8 @@ fn main() {
 }
 
 fn parse_input<'a>(src: &'a str) -> (Grid<char>, Vec<char>) {
-    let (grid, moves) = src.split_once("\n\n").unwrap();
-    let grid: Vec<Vec<char>> = grid
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    let grid: Grid<char> = Grid::new(grid);
+    let [grid, moves] = sections_n(src).expect("failed to parse input");
+    let grid: Grid<char> = Grid::parse(&grid).expect("failed to parse grid");
     let moves: Vec<char> = moves
         .lines()
         .map(|l| l.trim().chars().collect::<Vec<char>>())
@@ -29,12 +25,8 @@ fn parse_input<'a>(src: &'a str) -> (Grid<char>, Vec<char>) {
 }
 
 fn parse_input2<'a>(src: &'a str) -> (Grid<char>, Vec<char>) {
-    let (grid, moves) = src.split_once("\n\n").unwrap();
-    let grid: Vec<Vec<char>> = grid
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    let grid: Grid<char> = Grid::new(grid);
+    let [grid, moves] = sections_n(src).expect("failed to parse input");
+    let grid: Grid<char> = Grid::parse(&grid).expect("failed to parse grid");
 
     let mut new_grid: Vec<char> = Vec::new();
     for (x, y) in grid.iter_points() {
@@ -92,11 +84,6 @@ fn move_robot(grid: &mut Grid<char>, curx: i32, cury: i32, d: (i32, i32)) -> (i3
 fn part1(src: &str) -> Result<i32, Error> {
     let (mut grid, moves) = parse_input(src);
 
-    const UP: (i32, i32) = (0, -1);
-    const RIGHT: (i32, i32) = (1, 0);
-    const DOWN: (i32, i32) = (0, 1);
-    const LEFT: (i32, i32) = (-1, 0);
-
     let mut curx: i32 = 0;
     let mut cury: i32 = 0;
 
@@ -107,19 +94,8 @@ fn part1(src: &str) -> Result<i32, Error> {
         }
     }
 
-    for m in moves.iter() {
-        if *m == '^' {
-            (curx, cury) = move_robot(&mut grid, curx, cury, UP);
-        }
-        if *m == '>' {
-            (curx, cury) = move_robot(&mut grid, curx, cury, RIGHT);
-        }
-        if *m == 'v' {
-            (curx, cury) = move_robot(&mut grid, curx, cury, DOWN);
-        }
-        if *m == '<' {
-            (curx, cury) = move_robot(&mut grid, curx, cury, LEFT);
-        }
+    for d in moves.iter().filter_map(|m| Dir4::from_char(*m)) {
+        (curx, cury) = move_robot(&mut grid, curx, cury, d.delta().into());
     }
 
     Ok(grid.iter_points().fold(0, |acc, (x, y)| {
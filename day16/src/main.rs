@@ -36,12 +36,7 @@ fn main() {
 }
 
 fn parse_input<'a>(src: &'a str) -> Grid<char> {
-    let grid: Vec<Vec<char>> = src
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    let grid: Grid<char> = Grid::new(grid);
-    grid
+    Grid::parse(src).expect("failed to parse grid")
 }
 
 fn dijkstra(grid: &Grid<char>, start: (i32, i32), goal: (i32, i32)) -> (i64, i64) {
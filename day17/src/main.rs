@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Error};
+use tinyparse::{all_consuming, terminated, ws, Parse};
 
 #[derive(Debug, Default, Clone)]
 enum Ins {
@@ -14,7 +15,8 @@ enum Ins {
     EMPTY,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Parse)]
+#[parse("Register A: {reg_a}\nRegister B: {reg_b}\nRegister C: {reg_c}\n\nProgram: {p}")]
 struct Program {
     reg_a: i64,
     reg_b: i64,
@@ -32,48 +34,22 @@ fn main() {
 }
 
 fn parse_input<'a>(src: &'a str) -> Program {
-    let mut pgm = Program::default();
-
-    if let Some((reg, ins)) = src.split_once("\n\n") {
-        let [a, b, c, ..] = reg.split("\n").collect::<Vec<_>>()[..] else {
-            panic!("failed to parse regs")
-        };
-
-        if let Some((_, a)) = a.split_once(": ") {
-            pgm.reg_a = a.parse().expect("failed to parse reg a");
-        }
-        if let Some((_, b)) = b.split_once(": ") {
-            pgm.reg_b = b.parse().expect("failed to parse reg a");
-        }
-        if let Some((_, c)) = c.split_once(": ") {
-            pgm.reg_c = c.parse().expect("failed to parse reg a");
-        }
-
-        if let Some((_, ins)) = ins.split_once(" ") {
-            let ins = ins
-                .trim()
-                .split(",")
-                .map(|s| s.parse::<u8>().expect("failed to parse"))
-                .collect::<Vec<_>>();
-
-            pgm.p = ins.clone().into_iter().map(|v| v as i64).collect();
-
-            let mut ins: &[u8] = &ins[..];
-            while let [a, b, rest @ ..] = &ins[..] {
-                pgm.input.push(match a {
-                    0 => Ins::ADV(*b),
-                    1 => Ins::BXL(*b),
-                    2 => Ins::BST(*b),
-                    3 => Ins::JNZ(*b),
-                    4 => Ins::BXC(*b),
-                    5 => Ins::OUT(*b),
-                    6 => Ins::BDV(*b),
-                    7 => Ins::CDV(*b),
-                    _ => Ins::EMPTY,
-                });
-                ins = rest;
-            }
-        }
+    let (_, mut pgm) = tinyparse::run(all_consuming(terminated(Program::parse, ws)), src)
+        .expect("failed to parse input");
+
+    for op in pgm.p.chunks_exact(2) {
+        let b = op[1] as u8;
+        pgm.input.push(match op[0] {
+            0 => Ins::ADV(b),
+            1 => Ins::BXL(b),
+            2 => Ins::BST(b),
+            3 => Ins::JNZ(b),
+            4 => Ins::BXC(b),
+            5 => Ins::OUT(b),
+            6 => Ins::BDV(b),
+            7 => Ins::CDV(b),
+            _ => Ins::EMPTY,
+        });
     }
     pgm
 }
@@ -2,8 +2,8 @@ use anyhow::{anyhow, Error};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
-use utils::grid::Grid;
-use utils::nums;
+use tinyparse::{from_lines, run};
+use utils::grid::{Grid, Point};
 
 const W: usize = 71;
 const H: usize = 71;
@@ -18,8 +18,9 @@ fn main() {
     println!("part 2: {:?}", part2(input).unwrap());
 }
 
-fn parse_input<'a>(src: &'a str) -> Vec<Vec<i32>> {
-    src.lines().map(|l| utils::nums(l)).collect::<Vec<_>>()
+fn parse_input<'a>(src: &'a str) -> Vec<(i32, i32)> {
+    let (_, blocks) = run(from_lines("{},{}"), src).expect("failed to parse input");
+    blocks
 }
 
 //for minheap
@@ -54,8 +55,6 @@ fn dijkstra(
     let mut dist: FxHashMap<(i32, i32), i64> = FxHashMap::default();
     let mut path_to: FxHashMap<(i32, i32), FxHashSet<(i32, i32)>> = FxHashMap::default();
 
-    const ADJ: [(i64, (i32, i32)); 4] = [(1, (0, -1)), (1, (1, 0)), (1, (0, 1)), (1, (-1, 0))];
-
     let path: FxHashSet<(i32, i32)> = FxHashSet::default();
     h.push(Node(0, start, path));
 
@@ -73,16 +72,15 @@ fn dijkstra(
             *path_to.entry(p).or_default() = path.clone();
         }
 
-        for (cost, pt) in ADJ {
-            let np = (p.0 + pt.0, p.1 + pt.1);
-            if grid.in_bounds(np.0, np.1) && *grid.at_unsafe(np.0, np.1) != '#' {
-                if score + cost < *dist.entry(np).or_insert(i64::MAX) {
-                    *dist.entry(np).or_insert(i64::MAX) = cost + score;
-                    let mut npath = path.clone();
-                    npath.insert(np);
-                    *path_to.entry(p).or_default() = npath.clone();
-                    h.push(Node(score + cost, np, npath));
-                }
+        for (np, _) in grid.neighbors_where(Point::from(p), |c| *c != '#') {
+            let np: (i32, i32) = np.into();
+            let cost = 1;
+            if score + cost < *dist.entry(np).or_insert(i64::MAX) {
+                *dist.entry(np).or_insert(i64::MAX) = cost + score;
+                let mut npath = path.clone();
+                npath.insert(np);
+                *path_to.entry(p).or_default() = npath.clone();
+                h.push(Node(score + cost, np, npath));
             }
         }
     }
@@ -97,10 +95,8 @@ fn part1(src: &str) -> Result<i64, Error> {
     let grid: &[char; W * H] = &['.'; W * H];
     let mut grid: Grid<char> = Grid::from_vec(&grid.to_vec(), W as i32, H as i32);
 
-    for b in blocks.iter().take(STEPS) {
-        if let &[x, y] = &b[..] {
-            *grid.at_mut(x, y) = '#';
-        }
+    for &(x, y) in blocks.iter().take(STEPS) {
+        *grid.at_mut(x, y) = '#';
     }
     let (score, path) = dijkstra(&mut grid, START, END);
     for p in path {
@@ -116,17 +112,15 @@ fn part2(src: &str) -> Result<(i32, i32), Error> {
     let mut grid: Grid<char> = Grid::from_vec(&grid.to_vec(), W as i32, H as i32);
     let (_, mut path) = dijkstra(&mut grid, START, END);
 
-    for b in blocks {
-        if let &[x, y] = &b[..] {
-            *grid.at_mut(x, y) = '#';
+    for (x, y) in blocks {
+        *grid.at_mut(x, y) = '#';
 
-            if path.contains(&(x, y)) {
-                let (score, p) = dijkstra(&mut grid, START, END);
-                if score == i64::MAX {
-                    return Ok((x, y));
-                }
-                path = p;
+        if path.contains(&(x, y)) {
+            let (score, p) = dijkstra(&mut grid, START, END);
+            if score == i64::MAX {
+                return Ok((x, y));
             }
+            path = p;
         }
     }
     Err(anyhow!("NOT BLOCKED"))
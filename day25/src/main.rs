@@ -13,11 +13,7 @@ fn parse_input<'a>(src: &'a str) -> Vec<Grid<char>> {
 
     let mut vs: Vec<Grid<char>> = Vec::new();
     for kl in kls {
-        let g: Vec<Vec<char>> = kl
-            .lines()
-            .map(|l| l.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        vs.push(Grid::new(g));
+        vs.push(Grid::parse(kl).expect("failed to parse grid"));
     }
     vs
 }
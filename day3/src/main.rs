@@ -1,7 +1,7 @@
 use anyhow::Error;
 use tinyparse::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Command {
     MUL(i32),
     DO,
@@ -15,62 +15,47 @@ fn main() {
     println!("part 2: {}", part2(input).unwrap());
 }
 
-fn part1(mut src: &str) -> Result<i32, Error> {
-    let mut total: i32 = 0;
-    while !src.is_empty() {
-        let (p, ns) = many0(parse_mul)(src).unwrap();
-        for n in ns {
-            match n {
-                Command::MUL(v) => total += v,
-                _ => continue,
-            }
-        }
-        (src, _) = next(p)?;
-    }
+fn part1(src: &str) -> Result<i32, Error> {
+    let mul = parse_mul.map(|c| match c {
+        Command::MUL(v) => v,
+        _ => 0,
+    });
+    let (_, total) = many0_fold(mul.or(bytes::any_byte.value(0)), 0, |a, v| a + v)(src.as_bytes())?;
     Ok(total)
 }
 
-fn part2(mut src: &str) -> Result<i32, Error> {
+fn part2(src: &str) -> Result<i32, Error> {
     let mut total = 0;
     let mut state: bool = true;
 
-    while !src.is_empty() {
-        let (p, ns) = many0(one_of!(parse_mul, parse_dont, parse_do))(src).unwrap();
-        for n in ns {
-            match n {
-                Command::MUL(v) if state => total += v,
-                Command::MUL(_) => continue,
-                Command::DO => state = true,
-                Command::DONT => state = false,
-            }
+    let (_, scan) = scan_for(one_of!(parse_mul, parse_dont, parse_do))(src.as_bytes())?;
+    for n in scan.items {
+        match n {
+            Command::MUL(v) if state => total += v,
+            Command::MUL(_) => continue,
+            Command::DO => state = true,
+            Command::DONT => state = false,
         }
-        (src, _) = next(p)?;
     }
 
     Ok(total)
 }
 
-fn parse_mul(s: &str) -> Result<(&str, Command), Error> {
-    let (p, a) = right(
-        lit("mul("),
-        take_max_n_while(3, |c: &char| c.is_ascii_digit()),
-    )(s)?;
-
-    let (p, b) = right(lit(","), take_max_n_while(3, |p: &char| p.is_ascii_digit()))(p)?;
-    let (p, _) = lit(")")(p)?;
-
-    let (a, b) = (a.parse::<i32>().unwrap(), b.parse::<i32>().unwrap());
-    Ok((p, Command::MUL(a * b)))
+fn parse_mul(s: &[u8]) -> PResult<&[u8], Command> {
+    right(tag(b"mul("), bytes::uint_n::<i32>(1, 3))
+        .skip(byte(b','))
+        .then(bytes::uint_n::<i32>(1, 3))
+        .skip(byte(b')'))
+        .map(|(a, b)| Command::MUL(a * b))
+        .parse(s)
 }
 
-fn parse_do(s: &str) -> Result<(&str, Command), Error> {
-    let (p, _) = lit("do")(s)?;
-    Ok((p, Command::DO))
+fn parse_do(s: &[u8]) -> PResult<&[u8], Command> {
+    tag(b"do").value(Command::DO).parse(s)
 }
 
-fn parse_dont(s: &str) -> Result<(&str, Command), Error> {
-    let (p, _) = lit("don't")(s)?;
-    Ok((p, Command::DONT))
+fn parse_dont(s: &[u8]) -> PResult<&[u8], Command> {
+    tag(b"don't").value(Command::DONT).parse(s)
 }
 
 #[cfg(test)]
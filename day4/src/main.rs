@@ -1,4 +1,5 @@
 use anyhow::Error;
+use utils::grid::Dir8;
 
 fn main() {
     let input = include_str!("../../input/day4/input.txt");
@@ -7,17 +8,6 @@ fn main() {
     println!("part 2: {}", part2(input).unwrap());
 }
 
-const DIRECTION: [(i32, i32); 8] = [
-    (1, 1),
-    (-1, -1),
-    (0, 1),
-    (0, -1),
-    (1, 0),
-    (-1, 0),
-    (-1, 1),
-    (1, -1),
-];
-
 fn grid_walk(grid: &Vec<Vec<char>>, dir: (i32, i32), x: i32, y: i32, word: &[char]) -> bool {
     if word.len() == 0 {
         return true;
@@ -41,10 +31,8 @@ fn part1(src: &str) -> Result<i32, Error> {
 
     for y in 0..grid.len() as i32 {
         for x in 0..grid[0].len() as i32 {
-            total += DIRECTION
-                .map(|d| grid_walk(&grid, d, x, y, &word))
-                .iter()
-                .filter(|p| **p)
+            total += Dir8::all()
+                .filter(|d| grid_walk(&grid, d.delta().into(), x, y, &word))
                 .count();
         }
     }
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Error};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
+use utils::input::sections_n;
 
 fn main() {
     let input = include_str!("../../input/day5/input.txt");
@@ -12,7 +13,7 @@ fn main() {
 fn part1(src: &str) -> Result<i32, Error> {
     let mut total = 0;
     let mut map: HashMap<&str, HashSet<&str>> = HashMap::new();
-    let (rules, updates) = src.split_once("\n\n").ok_or(anyhow!("failed to parse"))?;
+    let [rules, updates] = sections_n(src)?;
 
     for r in rules.lines() {
         let (p, b) = r.split_once("|").ok_or(anyhow!("failed to parse rule"))?;
@@ -53,7 +54,7 @@ fn fix_update(mut up: Vec<&str>, m: &HashMap<&str, HashSet<&str>>) -> i32 {
 fn part2(src: &str) -> Result<i32, Error> {
     let mut total = 0;
     let mut map: HashMap<&str, HashSet<&str>> = HashMap::new();
-    let (rules, updates) = src.split_once("\n\n").ok_or(anyhow!("failed to parse"))?;
+    let [rules, updates] = sections_n(src)?;
 
     for r in rules.lines() {
         let (p, b) = r.split_once("|").ok_or(anyhow!("failed to parse rule"))?;
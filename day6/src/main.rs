@@ -1,20 +1,13 @@
 use anyhow::Error;
 //use std::collections::HashSet;
 use rustc_hash::FxHashSet;
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Dir {
-    UP,
-    RIGHT,
-    DOWN,
-    LEFT,
-}
+use utils::grid::Dir4;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct State {
     x: i32,
     y: i32,
-    d: Dir,
+    d: Dir4,
 }
 
 fn main() {
@@ -28,35 +21,17 @@ fn in_bounds(x: i32, y: i32, grid: &Vec<Vec<char>>) -> bool {
     x >= 0 && y >= 0 && x < grid[0].len() as i32 && y < grid.len() as i32
 }
 
-fn next_dir(d: Dir) -> Dir {
-    match d {
-        Dir::UP => Dir::RIGHT,
-        Dir::RIGHT => Dir::DOWN,
-        Dir::DOWN => Dir::LEFT,
-        Dir::LEFT => Dir::UP,
-    }
-}
-
 fn peek(s: &State, grid: &Vec<Vec<char>>) -> Option<(State, char)> {
-    let mut ns = s.clone();
-    match s.d {
-        Dir::UP if in_bounds(s.x, s.y - 1, &grid) => {
-            ns.y -= 1;
-            Some((ns, grid[ns.y as usize][ns.x as usize]))
-        }
-        Dir::RIGHT if in_bounds(s.x + 1, s.y, &grid) => {
-            ns.x += 1;
-            Some((ns, grid[ns.y as usize][ns.x as usize]))
-        }
-        Dir::DOWN if in_bounds(s.x, s.y + 1, &grid) => {
-            ns.y += 1;
-            Some((ns, grid[ns.y as usize][ns.x as usize]))
-        }
-        Dir::LEFT if in_bounds(s.x - 1, s.y, &grid) => {
-            ns.x -= 1;
-            Some((ns, grid[ns.y as usize][ns.x as usize]))
-        }
-        _ => None,
+    let d = s.d.delta();
+    let ns = State {
+        x: s.x + d.x,
+        y: s.y + d.y,
+        d: s.d,
+    };
+    if in_bounds(ns.x, ns.y, &grid) {
+        Some((ns, grid[ns.y as usize][ns.x as usize]))
+    } else {
+        None
     }
 }
 
@@ -70,7 +45,7 @@ fn has_cycle(mut s: State, grid: &Vec<Vec<char>>) -> bool {
         }
 
         match c {
-            '#' | 'O' => s.d = next_dir(ns.d),
+            '#' | 'O' => s.d = ns.d.turn_right(),
             _ => s = ns,
         }
         vv.insert(s);
@@ -86,14 +61,14 @@ fn part1(src: &str) -> Result<i32, Error> {
     let mut state = State {
         x: 0,
         y: 0,
-        d: Dir::UP,
+        d: Dir4::Up,
     };
 
     'outer: for y in 0..grid.len() as i32 {
         for x in 0..grid[0].len() as i32 {
             if grid[y as usize][x as usize] == '^' {
                 grid[y as usize][x as usize] = 'X';
-                state = State { x, y, d: Dir::UP };
+                state = State { x, y, d: Dir4::Up };
                 break 'outer;
             }
         }
@@ -103,7 +78,7 @@ fn part1(src: &str) -> Result<i32, Error> {
 
     while let Some((next, c)) = peek(&state, &grid) {
         if c == '#' {
-            state.d = next_dir(state.d);
+            state.d = state.d.turn_right();
         } else {
             if c == '.' && !path.contains(&(next.x, next.y)) {
                 path.insert((next.x, next.y));
@@ -124,13 +99,13 @@ fn part2(src: &str) -> Result<i32, Error> {
     let mut state = State {
         x: 0,
         y: 0,
-        d: Dir::UP,
+        d: Dir4::Up,
     };
 
     'outer: for y in 0..grid.len() as i32 {
         for x in 0..grid[0].len() as i32 {
             if grid[y as usize][x as usize] == '^' {
-                state = State { x, y, d: Dir::UP };
+                state = State { x, y, d: Dir4::Up };
                 break 'outer;
             }
         }
@@ -142,7 +117,7 @@ fn part2(src: &str) -> Result<i32, Error> {
     //find guard path
     while let Some((next, c)) = peek(&state, &grid) {
         if c == '#' {
-            state.d = next_dir(state.d);
+            state.d = state.d.turn_right();
         } else {
             if c == '.' && !path.contains(&(next.x, next.y)) {
                 path.insert((next.x, next.y));
@@ -1,4 +1,5 @@
 use anyhow::Error;
+use tinyparse::*;
 
 fn main() {
     let input = include_str!("../../input/day7/input.txt");
@@ -8,18 +9,15 @@ fn main() {
 }
 
 fn parse_input(src: &str) -> Vec<(i64, Vec<i64>)> {
-    src.lines()
-        .map(|l| l.split_once(':').unwrap())
-        .map(|(v, ts)| (v, ts.trim().split(" ").collect::<Vec<_>>()))
-        .map(|(v, ts)| {
-            (
-                v.parse::<i64>().unwrap(),
-                ts.into_iter()
-                    .map(|v| v.parse::<i64>().unwrap())
-                    .collect::<Vec<_>>(),
-            )
-        })
-        .collect::<Vec<_>>()
+    let sp = Skipper::spaces();
+    let equation = tuple((
+        terminated(uint::<i64>(), sp.symbol(":")),
+        many1(sp.lexeme(uint::<i64>())),
+    ));
+
+    let equations = all_consuming(terminated(sep_by(equation, lit("\n")), ws));
+    let (_, equations) = run(equations, src).expect("failed to parse input");
+    equations
 }
 
 fn solve(t: i64, target: i64, vs: &[i64]) -> bool {
@@ -90,4 +88,10 @@ mod tests {
 
         assert_eq!(11387, part2(src).unwrap());
     }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(0, part1("").unwrap());
+        assert_eq!(0, part2("").unwrap());
+    }
 }
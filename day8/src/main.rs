@@ -1,7 +1,7 @@
 use anyhow::Error;
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
-use utils::grid::Grid;
+use utils::grid::{Grid, Point};
 
 fn main() {
     let input = include_str!("../../input/day8/input.txt");
@@ -11,47 +11,34 @@ fn main() {
 }
 
 fn parse_input(src: &str) -> Grid<char> {
-    let vs = src
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    Grid::new(vs)
-}
-
-fn distance_signed(a1: (i32, i32), a2: (i32, i32)) -> (i32, i32) {
-    ((a1.0 - a2.0), (a1.1 - a2.1))
-}
-
-fn add_points(a1: (i32, i32), a2: (i32, i32)) -> (i32, i32) {
-    (a1.0 + a2.0, a1.1 + a2.1)
+    Grid::parse(src).expect("failed to parse grid")
 }
 
 fn part1(src: &str) -> Result<i64, Error> {
     let grid = parse_input(src);
 
     //find antennas
-    let mut ant_map: FxHashMap<char, Vec<(i32, i32)>> = FxHashMap::default();
-    for (x, y) in grid.iter_points() {
-        if grid.at(x, y) != Some(&'.') {
-            let a = grid.at(x, y).unwrap();
-            ant_map.entry(*a).or_default().push((x, y));
+    let mut ant_map: FxHashMap<char, Vec<Point>> = FxHashMap::default();
+    for p in grid.iter_points().map(Point::from) {
+        if grid[p] != '.' {
+            ant_map.entry(grid[p]).or_default().push(p);
         }
     }
 
-    let mut antinodes: FxHashSet<(i32, i32)> = FxHashSet::default();
+    let mut antinodes: FxHashSet<Point> = FxHashSet::default();
 
     for (_, ats) in &ant_map {
         for cs in ats.iter().combinations(2) {
             if let [a1, a2] = cs[..] {
-                let (dx, dy) = distance_signed(*a1, *a2);
+                let d = *a1 - *a2;
 
-                let ant1 = add_points(*a1, (dx, dy));
-                if grid.in_bounds(ant1.0, ant1.1) {
+                let ant1 = *a1 + d;
+                if grid.contains(ant1) {
                     antinodes.insert(ant1);
                 }
 
-                let ant1 = add_points(*a2, (-dx, -dy));
-                if grid.in_bounds(ant1.0, ant1.1) {
+                let ant1 = *a2 - d;
+                if grid.contains(ant1) {
                     antinodes.insert(ant1);
                 }
             }
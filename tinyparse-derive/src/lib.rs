@@ -0,0 +1,477 @@
+//! `#[derive(Parse)]` and `scan!` for tinyparse. See `tinyparse::Parse` for
+//! the pattern syntax.
+use std::collections::BTreeMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, GenericParam, Ident,
+    Lifetime, LifetimeParam, LitStr, Result, Token, Type,
+};
+
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// What a `#[parse(...)]` attribute says.
+enum Attr {
+    Pattern(LitStr),
+    Skip,
+}
+
+fn parse_attr(attrs: &[Attribute]) -> Result<Option<Attr>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("parse")) {
+        if found.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate #[parse] attribute"));
+        }
+        found = Some(attr.parse_args_with(|input: syn::parse::ParseStream| {
+            if input.peek(LitStr) {
+                Ok(Attr::Pattern(input.parse()?))
+            } else {
+                let ident: Ident = input.parse()?;
+                if ident == "skip" {
+                    Ok(Attr::Skip)
+                } else {
+                    Err(Error::new(
+                        ident.span(),
+                        "expected a pattern string or `skip`",
+                    ))
+                }
+            }
+        })?);
+    }
+    Ok(found)
+}
+
+fn pattern_attr(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    match parse_attr(attrs)? {
+        Some(Attr::Pattern(p)) => Ok(Some(p)),
+        Some(Attr::Skip) => {
+            let attr = attrs.iter().find(|a| a.path().is_ident("parse")).unwrap();
+            Err(Error::new_spanned(
+                attr,
+                "`skip` is only allowed on fields and variants",
+            ))
+        }
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Seg {
+    Name(String),
+    Index(usize),
+}
+
+enum Piece {
+    Lit(String),
+    Field(Vec<Seg>),
+}
+
+/// Split a pattern into literal text and the contents of `{...}`
+/// placeholders. `{{` and `}}` stand for literal braces.
+fn split_pattern(lit: &LitStr) -> Result<Vec<(String, Option<String>)>> {
+    let src = lit.value();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut hole = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => hole.push(c),
+                        None => {
+                            let msg = format!("unclosed placeholder `{{{}`", hole);
+                            return Err(Error::new(lit.span(), msg));
+                        }
+                    }
+                }
+                pieces.push((std::mem::take(&mut text), Some(hole)));
+            }
+            '}' => return Err(Error::new(lit.span(), "unmatched `}` in pattern, use `}}`")),
+            c => text.push(c),
+        }
+    }
+
+    pieces.push((text, None));
+    Ok(pieces)
+}
+
+/// Split a pattern into literal text and `{field.0.1}` placeholders.
+fn parse_pattern(lit: &LitStr) -> Result<Vec<Piece>> {
+    let err = |msg: String| Error::new(lit.span(), msg);
+    let mut pieces = Vec::new();
+
+    for (text, hole) in split_pattern(lit)? {
+        if !text.is_empty() {
+            pieces.push(Piece::Lit(text));
+        }
+        let Some(name) = hole else {
+            continue;
+        };
+        let path = name
+            .split('.')
+            .map(|s| match s.trim() {
+                "" => Err(err(format!("invalid placeholder `{{{}}}`", name))),
+                s if s.chars().all(|c| c.is_ascii_digit()) => Ok(Seg::Index(s.parse().unwrap())),
+                s => Ok(Seg::Name(s.to_string())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if path[1..].iter().any(|s| matches!(s, Seg::Name(_))) {
+            return Err(err(format!(
+                "only tuple indices may follow the field in `{{{}}}`",
+                name
+            )));
+        }
+        pieces.push(Piece::Field(path));
+    }
+
+    Ok(pieces)
+}
+
+/// How the value of a field is assembled from parsed placeholders.
+enum Node {
+    Leaf(Ident),
+    Tuple(BTreeMap<usize, Node>),
+}
+
+impl Node {
+    fn insert(&mut self, path: &[usize], var: Ident) -> bool {
+        let Node::Tuple(children) = self else {
+            return false;
+        };
+        match path {
+            [i] => children.insert(*i, Node::Leaf(var)).is_none(),
+            [i, rest @ ..] => children
+                .entry(*i)
+                .or_insert_with(|| Node::Tuple(BTreeMap::new()))
+                .insert(rest, var),
+            [] => false,
+        }
+    }
+
+    fn expr(&self) -> std::result::Result<TokenStream2, String> {
+        match self {
+            Node::Leaf(v) => Ok(quote!(#v)),
+            Node::Tuple(children) => {
+                let mut items = Vec::new();
+                for (n, (i, child)) in children.iter().enumerate() {
+                    if *i != n {
+                        return Err(format!("tuple element {} is never parsed", n));
+                    }
+                    items.push(child.expr()?);
+                }
+                Ok(quote!((#(#items,)*)))
+            }
+        }
+    }
+}
+
+/// A field as named in patterns, with how to write it in a constructor.
+struct Field {
+    key: Seg,
+    member: TokenStream2,
+    attr: Option<Attr>,
+}
+
+fn fields_of(fields: &Fields) -> Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let (key, member) = match &f.ident {
+                Some(id) => (Seg::Name(id.to_string()), quote!(#id)),
+                None => {
+                    let idx = syn::Index::from(i);
+                    (Seg::Index(i), quote!(#idx))
+                }
+            };
+            Ok(Field {
+                key,
+                member,
+                attr: parse_attr(&f.attrs)?,
+            })
+        })
+        .collect()
+}
+
+fn seg_str(s: &Seg) -> String {
+    match s {
+        Seg::Name(n) => n.clone(),
+        Seg::Index(i) => i.to_string(),
+    }
+}
+
+/// Statements that parse `s` according to the patterns and then build
+/// `ctor { .. }`, leaving fields no pattern mentions at their default.
+fn body(
+    ctor: TokenStream2,
+    fields: &Fields,
+    pattern: Option<LitStr>,
+    span: Span,
+) -> Result<TokenStream2> {
+    let fs = fields_of(fields)?;
+
+    let mut patterns: Vec<(LitStr, Vec<Piece>)> = Vec::new();
+    match pattern {
+        Some(p) => {
+            if fs.iter().any(|f| f.attr.is_some()) {
+                return Err(Error::new(
+                    p.span(),
+                    "field patterns cannot be combined with a pattern on the type or variant",
+                ));
+            }
+            let pieces = parse_pattern(&p)?;
+            patterns.push((p, pieces));
+        }
+        None => {
+            for f in &fs {
+                let p = match &f.attr {
+                    Some(Attr::Pattern(p)) => p.clone(),
+                    Some(Attr::Skip) => continue,
+                    None => LitStr::new(&format!("{{{}}}", seg_str(&f.key)), span),
+                };
+                let pieces = parse_pattern(&p)?;
+                patterns.push((p, pieces));
+            }
+        }
+    }
+
+    let mut stmts = Vec::new();
+    let mut nodes: Vec<Option<Node>> = fs.iter().map(|_| None).collect();
+    let mut n = 0usize;
+
+    for (lit, pieces) in &patterns {
+        for piece in pieces {
+            match piece {
+                Piece::Lit(text) => {
+                    stmts.push(quote!(let (s, _) = ::tinyparse::lit(#text)(s)?;));
+                }
+                Piece::Field(path) => {
+                    let var = format_ident!("__f{}", n);
+                    n += 1;
+                    stmts.push(quote!(let (s, #var) = ::tinyparse::Parse::parse(s)?;));
+
+                    let name = path.iter().map(seg_str).collect::<Vec<_>>().join(".");
+                    let Some(i) = fs.iter().position(|f| f.key == path[0]) else {
+                        return Err(Error::new(
+                            lit.span(),
+                            format!("no field `{}`", seg_str(&path[0])),
+                        ));
+                    };
+                    let indices: Vec<usize> = path[1..]
+                        .iter()
+                        .map(|s| match s {
+                            Seg::Index(i) => *i,
+                            Seg::Name(_) => unreachable!(),
+                        })
+                        .collect();
+
+                    let ok = match (&mut nodes[i], indices.is_empty()) {
+                        (slot @ None, true) => {
+                            *slot = Some(Node::Leaf(var));
+                            true
+                        }
+                        (slot @ None, false) => slot
+                            .insert(Node::Tuple(BTreeMap::new()))
+                            .insert(&indices, var),
+                        (Some(node), false) => node.insert(&indices, var),
+                        (Some(_), true) => false,
+                    };
+                    if !ok {
+                        return Err(Error::new(
+                            lit.span(),
+                            format!("`{{{}}}` overlaps another placeholder", name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut inits = Vec::new();
+    for (f, node) in fs.iter().zip(&nodes) {
+        let member = &f.member;
+        let value = match node {
+            Some(node) => node
+                .expr()
+                .map_err(|e| Error::new(span, format!("field `{}`: {}", seg_str(&f.key), e)))?,
+            None => quote!(::core::default::Default::default()),
+        };
+        inits.push(quote!(#member: #value));
+    }
+
+    Ok(quote! {
+        #(#stmts)*
+        Ok((s, #ctor { #(#inits),* }))
+    })
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let span = name.span();
+
+    // reuse the type's lifetime so borrowed fields can point into the input
+    let mut generics = input.generics.clone();
+    let lt = match generics.lifetimes().next() {
+        Some(l) => l.lifetime.clone(),
+        None => {
+            let lt = Lifetime::new("'p", Span::call_site());
+            generics
+                .params
+                .insert(0, GenericParam::Lifetime(LifetimeParam::new(lt.clone())));
+            lt
+        }
+    };
+    for p in generics.type_params_mut() {
+        p.bounds.push(syn::parse_quote!(::tinyparse::Parse<#lt>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let parse = match &input.data {
+        Data::Struct(data) => body(
+            quote!(Self),
+            &data.fields,
+            pattern_attr(&input.attrs)?,
+            span,
+        )?,
+        Data::Enum(data) => {
+            if let Some(a) = input.attrs.iter().find(|a| a.path().is_ident("parse")) {
+                return Err(Error::new_spanned(
+                    a,
+                    "put #[parse] on the variants of an enum",
+                ));
+            }
+
+            let mut arms = Vec::new();
+            for v in &data.variants {
+                let pattern = match parse_attr(&v.attrs)? {
+                    Some(Attr::Skip) => continue,
+                    Some(Attr::Pattern(p)) => Some(p),
+                    None if v.fields.is_empty() => {
+                        Some(LitStr::new(&v.ident.to_string(), v.ident.span()))
+                    }
+                    None => None,
+                };
+                let ident = &v.ident;
+                let b = body(quote!(Self::#ident), &v.fields, pattern, v.ident.span())?;
+                arms.push(quote! {
+                    let r = (|s: &#lt str| -> ::tinyparse::PResult<&#lt str, Self> { #b })(s);
+                    match r {
+                        Ok(v) => return Ok(v),
+                        Err(e) => errs.push(e),
+                    }
+                });
+            }
+
+            quote! {
+                let mut errs = ::std::vec::Vec::new();
+                #(#arms)*
+                Err(::tinyparse::PError::new(::tinyparse::ErrorKind::Alt(errs), s))
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(span, "#[derive(Parse)] does not support unions"));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::tinyparse::Parse<#lt> for #name #ty_generics #where_clause {
+            fn parse(s: &#lt str) -> ::tinyparse::PResult<&#lt str, Self> {
+                #parse
+            }
+        }
+    })
+}
+
+struct ScanInput {
+    fmt: LitStr,
+    input: Expr,
+}
+
+impl syn::parse::Parse for ScanInput {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let fmt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let expr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(ScanInput { fmt, input: expr })
+    }
+}
+
+/// `scan!("p={},{} v={},{}", line)` matches `line` against the format and
+/// returns `Result<T, PError>`, where `T` is the value of the single
+/// placeholder or a tuple of all of them. A placeholder may name its type,
+/// as in `{i64}`; otherwise it is inferred. The whole input must match.
+#[proc_macro]
+pub fn scan(input: TokenStream) -> TokenStream {
+    let ScanInput { fmt, input } = parse_macro_input!(input as ScanInput);
+    match expand_scan(fmt, input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_scan(fmt: LitStr, input: Expr) -> Result<TokenStream2> {
+    // keep the generated locals apart from any in `input`
+    let span = Span::mixed_site();
+    let mut stmts = Vec::new();
+    let mut vars = Vec::new();
+
+    for (text, hole) in split_pattern(&fmt)? {
+        if !text.is_empty() {
+            stmts.push(quote_spanned!(span=> let (s, _) = ::tinyparse::lit(#text)(s)?;));
+        }
+        let Some(hole) = hole else {
+            continue;
+        };
+        let var = format_ident!("v{}", vars.len(), span = span);
+        let parse = match hole.trim() {
+            "" => quote_spanned!(span=> ::tinyparse::Parse::parse),
+            ty => {
+                let ty: Type = syn::parse_str(ty).map_err(|e| Error::new(fmt.span(), e))?;
+                quote_spanned!(span=> <#ty as ::tinyparse::Parse>::parse)
+            }
+        };
+        stmts.push(quote_spanned!(span=> let (s, #var) = #parse(s)?;));
+        vars.push(var);
+    }
+
+    let ret = match &vars[..] {
+        [v] => quote_spanned!(span=> #v),
+        vs => quote_spanned!(span=> (#(#vs),*)),
+    };
+
+    Ok(quote_spanned! {span=>
+        {
+            let src: &str = #input;
+            (|| -> ::core::result::Result<_, ::tinyparse::PError> {
+                let s = src;
+                #(#stmts)*
+                ::tinyparse::eof(s)?;
+                Ok(#ret)
+            })()
+            .map_err(|e| e.locate(src))
+        }
+    })
+}
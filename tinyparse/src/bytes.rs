@@ -0,0 +1,75 @@
+//! Byte-oriented primitives for `&[u8]` input. Puzzle inputs are ASCII, so
+//! these skip the char boundary handling of the `&str` primitives.
+//!
+//! The numeric parsers here mirror the `&str` ones at the crate root.
+use crate::num::{float_in, radix_int};
+use crate::{ErrorKind, Integer, PError, PResult};
+
+pub fn any_byte(s: &[u8]) -> PResult<&[u8], u8, PError> {
+    match s.split_first() {
+        Some((b, p)) => Ok((p, *b)),
+        None => Err(PError::new(ErrorKind::Eof, s)),
+    }
+}
+
+pub fn byte<'p>(b: u8) -> impl Fn(&'p [u8]) -> PResult<&'p [u8], u8, PError> {
+    return move |s| match s.split_first() {
+        Some((c, p)) if *c == b => Ok((p, b)),
+        _ => Err(PError::new(ErrorKind::Expected((b as char).to_string()), s)),
+    };
+}
+
+pub fn tag<'p>(t: &'p [u8]) -> impl Fn(&'p [u8]) -> PResult<&'p [u8], &'p [u8], PError> {
+    return move |s| {
+        if s.starts_with(t) {
+            let (p, r) = s.split_at(t.len());
+            Ok((r, p))
+        } else {
+            Err(PError::new(
+                ErrorKind::Expected(String::from_utf8_lossy(t).into_owned()),
+                s,
+            ))
+        }
+    };
+}
+
+pub fn take_while_byte<'p, P>(
+    predicate: P,
+) -> impl Fn(&'p [u8]) -> PResult<&'p [u8], &'p [u8], PError>
+where
+    P: Fn(&u8) -> bool,
+{
+    return move |s| {
+        let n = s.iter().position(|b| !predicate(b)).unwrap_or(s.len());
+        let (p, r) = s.split_at(n);
+        Ok((r, p))
+    };
+}
+
+pub fn uint<'p, T: Integer>() -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(10, false, 1, usize::MAX)
+}
+
+pub fn int<'p, T: Integer>() -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(10, true, 1, usize::MAX)
+}
+
+pub fn uint_n<'p, T: Integer>(min: usize, max: usize) -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(10, false, min, max)
+}
+
+pub fn int_n<'p, T: Integer>(min: usize, max: usize) -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(10, true, min, max)
+}
+
+pub fn hex<'p, T: Integer>() -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(16, false, 1, usize::MAX)
+}
+
+pub fn bin<'p, T: Integer>() -> impl Fn(&'p [u8]) -> PResult<&'p [u8], T> {
+    radix_int(2, false, 1, usize::MAX)
+}
+
+pub fn float<'p>() -> impl Fn(&'p [u8]) -> PResult<&'p [u8], f64> {
+    float_in()
+}
@@ -0,0 +1,441 @@
+//! Deserialize input into `serde` types with a format string, as with
+//! `formatted` and `from_lines` but for any `Deserialize` type.
+//!
+//! Holes are filled in order, or by field name when they are named, as in
+//! `"p={x},{y}"`. A value that is a sequence, tuple or struct itself is read
+//! from one hole as a comma separated list, and enums match unit variants
+//! by name.
+use std::fmt::Display;
+
+use serde::de::value::{BorrowedStrDeserializer, StrDeserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use crate::format::{lines, pieces, Scanner};
+use crate::{int, lit, next, ErrorKind, PError, PResult, Parse};
+
+impl de::Error for PError {
+    fn custom<T: Display>(msg: T) -> Self {
+        PError::unanchored(ErrorKind::Invalid(msg.to_string()))
+    }
+}
+
+/// Match input against `fmt` and deserialize the holes into `T`.
+///
+/// Panics if `fmt` is malformed or has holes left over once `T` is read.
+pub fn formatted<'de, T: Deserialize<'de>>(fmt: &str) -> impl Fn(&'de str) -> PResult<&'de str, T> {
+    let pieces = pieces(fmt);
+    return move |s| {
+        let mut sc = Scanner::new(&pieces, s);
+        let v = T::deserialize(Holes { sc: &mut sc }).map_err(|e| e.anchor(s))?;
+        let (p, _) = sc.finish()?;
+        Ok((p, v))
+    };
+}
+
+/// Read every line of the input with `de::formatted(fmt)`.
+pub fn from_lines<'de, T: Deserialize<'de>>(
+    fmt: &str,
+) -> impl Fn(&'de str) -> PResult<&'de str, Vec<T>> {
+    lines(formatted(fmt))
+}
+
+/// Match the text before the next hole, then read its value with `f`.
+fn fill<'de, T>(
+    sc: &mut Scanner<'_, 'de>,
+    f: impl FnOnce(Value<'_, 'de>) -> Result<T, PError>,
+) -> Result<T, PError> {
+    sc.lit()?;
+    let start = sc.s;
+    let v = f(Value { s: &mut sc.s }).map_err(|e| e.anchor(start))?;
+    sc.fill();
+    Ok(v)
+}
+
+/// Deserializer for the whole format string.
+struct Holes<'a, 'b, 'de> {
+    sc: &'b mut Scanner<'a, 'de>,
+}
+
+impl<'de> SeqAccess<'de> for Holes<'_, '_, 'de> {
+    type Error = PError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, PError> {
+        match self.sc.next_hole() {
+            Some(_) => fill(self.sc, |v| seed.deserialize(v)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Holes<'_, '_, 'de> {
+    type Error = PError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, PError> {
+        match self.sc.next_hole() {
+            Some(name) => seed.deserialize(StrDeserializer::new(name)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, PError> {
+        fill(self.sc, |v| seed.deserialize(v))
+    }
+}
+
+/// Read a single hole.
+macro_rules! forward_to_value {
+    ($($method:ident)*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+            fill(self.sc, |v| v.$method(visitor))
+        })*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Holes<'_, '_, 'de> {
+    type Error = PError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        match self.sc.next_hole() {
+            Some(name) if !name.is_empty() => visitor.visit_map(self),
+            _ => visitor.visit_seq(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        fill(self.sc, |v| v.deserialize_enum(name, variants, visitor))
+    }
+
+    serde::forward_to_deserialize_any! {
+        seq tuple tuple_struct map
+    }
+
+    forward_to_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+        deserialize_unit deserialize_identifier deserialize_ignored_any
+    }
+}
+
+/// Deserializer for the value of one hole, reading from `s` and leaving it
+/// at the end of the value.
+struct Value<'c, 'de> {
+    s: &'c mut &'de str,
+}
+
+impl<'de> Value<'_, 'de> {
+    fn take<T>(&mut self, op: impl Fn(&'de str) -> PResult<&'de str, T>) -> Result<T, PError> {
+        let (p, v) = op(self.s)?;
+        *self.s = p;
+        Ok(v)
+    }
+}
+
+/// Read a value with its `Parse` impl.
+macro_rules! parse_value {
+    ($($method:ident $t:ty => $visit:ident)*) => {
+        $(fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, PError> {
+            let v = self.take(<$t>::parse)?;
+            visitor.$visit(v)
+        })*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value<'_, 'de> {
+    type Error = PError;
+
+    /// Guess from the input: an integer, a float or else a word.
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, PError> {
+        if let Ok((p, v)) = int::<i64>()(self.s) {
+            if !p.starts_with('.') {
+                *self.s = p;
+                return visitor.visit_i64(v);
+            }
+        }
+        if let Ok(v) = self.take(f64::parse) {
+            return visitor.visit_f64(v);
+        }
+        let w = self.take(<&str>::parse)?;
+        visitor.visit_borrowed_str(w)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, PError> {
+        let b = self.take(|s| match lit("true")(s) {
+            Ok((p, _)) => Ok((p, true)),
+            Err(_) => lit("false")(s).map(|(p, _)| (p, false)),
+        })?;
+        visitor.visit_bool(b)
+    }
+
+    parse_value! {
+        deserialize_i8 i8 => visit_i8
+        deserialize_i16 i16 => visit_i16
+        deserialize_i32 i32 => visit_i32
+        deserialize_i64 i64 => visit_i64
+        deserialize_i128 i128 => visit_i128
+        deserialize_u8 u8 => visit_u8
+        deserialize_u16 u16 => visit_u16
+        deserialize_u32 u32 => visit_u32
+        deserialize_u64 u64 => visit_u64
+        deserialize_u128 u128 => visit_u128
+        deserialize_f32 f64 => visit_f64
+        deserialize_f64 f64 => visit_f64
+        deserialize_str &str => visit_borrowed_str
+        deserialize_string &str => visit_borrowed_str
+        deserialize_identifier &str => visit_borrowed_str
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, PError> {
+        let c = self.take(next)?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, PError> {
+        Err(de::Error::custom("bytes are not supported"))
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, PError> {
+        Err(de::Error::custom("bytes are not supported"))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+        visitor.visit_seq(Commas {
+            s: self.s,
+            first: true,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, PError> {
+        Err(de::Error::custom("maps are not supported"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PError> {
+        let w = self.take(<&str>::parse)?;
+        visitor.visit_enum(BorrowedStrDeserializer::new(w))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PError> {
+        self.deserialize_any(visitor)
+    }
+}
+
+/// Elements of a comma separated list within one hole.
+struct Commas<'c, 'de> {
+    s: &'c mut &'de str,
+    first: bool,
+}
+
+impl<'de> SeqAccess<'de> for Commas<'_, 'de> {
+    type Error = PError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, PError> {
+        if !self.first {
+            match lit(",")(self.s) {
+                Ok((p, _)) => *self.s = p,
+                Err(_) => return Ok(None),
+            }
+        }
+        self.first = false;
+
+        let start = *self.s;
+        seed.deserialize(Value { s: &mut *self.s })
+            .map(Some)
+            .map_err(|e| e.anchor(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::run;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Robot {
+        x: i32,
+        y: i32,
+        v: (i32, i32),
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "UPPERCASE")]
+    enum Op {
+        And,
+        Xor,
+        Or,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Gate<'a> {
+        a: &'a str,
+        op: Op,
+        b: &'a str,
+        out: &'a str,
+    }
+
+    #[test]
+    fn test_de_formatted() {
+        let robot = formatted::<Robot>("p={x},{y} v={v}");
+        let (_, r) = robot("p=0,4 v=3,-3").unwrap();
+        assert_eq!(
+            r,
+            Robot {
+                x: 0,
+                y: 4,
+                v: (3, -3)
+            }
+        );
+
+        // named holes can come in any order
+        let robot = formatted::<Robot>("v={v} p={y},{x}");
+        let (_, r) = robot("v=3,-3 p=4,0").unwrap();
+        assert_eq!(
+            r,
+            Robot {
+                x: 0,
+                y: 4,
+                v: (3, -3)
+            }
+        );
+
+        let gate = formatted::<Gate>("{} {} {} -> {}");
+        let (_, g) = gate("x00 XOR y00 -> z00").unwrap();
+        assert_eq!(g.op, Op::Xor);
+        assert_eq!((g.a, g.b, g.out), ("x00", "y00", "z00"));
+
+        let (_, (n, ops)) = formatted::<(u8, Vec<u8>)>("{}: {}")("3: 0,1,5").unwrap();
+        assert_eq!((n, ops), (3, vec![0, 1, 5]));
+    }
+
+    #[test]
+    fn test_de_errors() {
+        let src = "x00 AND y00 -> z00
+x01 NOT y01 -> z01
+";
+        let e = run(from_lines::<Gate>("{} {} {} -> {}"), src).unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Invalid(_)));
+        assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((2, 5)));
+
+        let e = run(
+            from_lines::<(i32, i32)>("{},{}"),
+            "1,2
+3,x
+",
+        )
+        .unwrap_err();
+        assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_de_from_lines() {
+        let src = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+";
+        let (_, rs) = run(from_lines::<Robot>("p={x},{y} v={v}"), src).unwrap();
+        assert_eq!(rs.len(), 2);
+        assert_eq!(
+            rs[1],
+            Robot {
+                x: 6,
+                y: 3,
+                v: (-1, -3)
+            }
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use thiserror::Error;
+
+use crate::Input;
+
+/// What a parser was looking for when it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A literal did not match.
+    Expected(String),
+    /// A character predicate rejected the input.
+    Predicate,
+    /// Fewer than `min` items matched.
+    MinCount { min: usize, found: usize },
+    /// The input matched but could not be converted, e.g. an out-of-range number.
+    Invalid(String),
+    /// Input ran out before the parser could finish.
+    Eof,
+    /// Input ran out partway through an expected literal, `needed` bytes
+    /// short. Reported by a `Stream` at the end of its input.
+    Incomplete { needed: usize },
+    /// Reading from a `Stream` failed.
+    Io(String),
+    /// Input was left over where the end of input was expected.
+    Trailing,
+    /// A negative lookahead matched.
+    Unexpected,
+    /// Every alternative of a choice failed.
+    Alt(Vec<PError>),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Expected(l) => write!(f, "expected {:?}", l),
+            ErrorKind::Predicate => write!(f, "predicate failed"),
+            ErrorKind::MinCount { min, found } => {
+                write!(f, "expected at least {} matches, found {}", min, found)
+            }
+            ErrorKind::Invalid(m) => write!(f, "invalid value: {}", m),
+            ErrorKind::Eof => write!(f, "unexpected end of input"),
+            ErrorKind::Incomplete { needed } => {
+                write!(f, "incomplete input, {} more bytes needed", needed)
+            }
+            ErrorKind::Io(e) => write!(f, "read error: {}", e),
+            ErrorKind::Trailing => write!(f, "expected end of input"),
+            ErrorKind::Unexpected => write!(f, "unexpected match"),
+            ErrorKind::Alt(es) => write!(f, "none of {} alternatives matched", es.len()),
+        }
+    }
+}
+
+/// Location of an error in the original input. `line` and `col` are 1-based,
+/// `col` counts chars rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Position {
+    /// Position of the start of input.
+    pub fn start() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            col: 1,
+        }
+    }
+
+    /// Resolve `p`, a position within input that starts at `self`.
+    pub(crate) fn advance(self, p: Position) -> Self {
+        Self {
+            offset: self.offset + p.offset,
+            line: self.line + p.line - 1,
+            col: if p.line == 1 {
+                self.col + p.col - 1
+            } else {
+                p.col
+            },
+        }
+    }
+
+    pub fn from_offset<I: Input>(src: I, offset: usize) -> Self {
+        let before = &src.input_bytes()[..offset];
+        let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+        let line_start = before
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        // count UTF-8 lead bytes so multi-byte chars are one column
+        let col = before[line_start..]
+            .iter()
+            .filter(|b| (**b & 0xC0) != 0x80)
+            .count()
+            + 1;
+        Self { offset, line, col }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (byte {})",
+            self.line, self.col, self.offset
+        )
+    }
+}
+
+/// Parse error. Parsers only ever see the tail of the input, so an error
+/// records how many bytes were left when it happened; `locate` turns that
+/// into a `Position` once the original input is known.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub struct PError {
+    pub kind: ErrorKind,
+    remaining: usize,
+    pub pos: Option<Position>,
+}
+
+impl PError {
+    pub fn new<I: Input>(kind: ErrorKind, at: I) -> Self {
+        Self {
+            kind,
+            remaining: at.input_len(),
+            pos: None,
+        }
+    }
+
+    /// Byte offset of the failure in `src`.
+    pub fn offset<I: Input>(&self, src: I) -> usize {
+        src.input_len().saturating_sub(self.remaining)
+    }
+
+    /// An error raised away from the input, such as a serde `custom` error,
+    /// to be placed by `anchor`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn unanchored(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            remaining: usize::MAX,
+            pos: None,
+        }
+    }
+
+    /// Place an unanchored error at `at`; other errors are returned as is.
+    #[cfg(feature = "serde")]
+    pub(crate) fn anchor<I: Input>(mut self, at: I) -> Self {
+        if self.remaining == usize::MAX {
+            self.remaining = at.input_len();
+        }
+        self
+    }
+
+    /// Re-anchor an error raised on a slice that ended `n` bytes before the
+    /// end of the input it was cut from.
+    pub(crate) fn shift(mut self, n: usize) -> Self {
+        self.remaining = self.remaining.saturating_add(n);
+        if let ErrorKind::Alt(es) = self.kind {
+            self.kind = ErrorKind::Alt(es.into_iter().map(|e| e.shift(n)).collect());
+        }
+        self
+    }
+
+    /// Resolve the error position (and that of any nested alternatives)
+    /// against the original input.
+    pub fn locate<I: Input>(self, src: I) -> Self {
+        self.locate_at(src, Position::start())
+    }
+
+    /// Like `locate`, for `src` that starts at `base` in some larger input.
+    pub(crate) fn locate_at<I: Input>(mut self, src: I, base: Position) -> Self {
+        self.pos = Some(base.advance(Position::from_offset(src, self.offset(src))));
+        if let ErrorKind::Alt(es) = self.kind {
+            self.kind = ErrorKind::Alt(es.into_iter().map(|e| e.locate_at(src, base)).collect());
+        }
+        self
+    }
+}
+
+impl fmt::Display for PError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.pos {
+            Some(p) => write!(f, "{} at {}", self.kind, p),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
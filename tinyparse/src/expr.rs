@@ -0,0 +1,154 @@
+use crate::{lit, PResult, Skipper};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+type Unary<O> = Box<dyn Fn(O) -> O>;
+type Binary<O> = Box<dyn Fn(O, O) -> O>;
+
+/// Operator-precedence (Pratt) expression parser. Operators are literals
+/// with a binding power; higher binds tighter. Each operator carries the
+/// function that combines its operands, so the output can be an AST or a
+/// value folded on the fly.
+pub struct Expr<'p, O, A> {
+    atom: A,
+    prefix: Vec<(&'p str, u32, Unary<O>)>,
+    infix: Vec<(&'p str, u32, Assoc, Binary<O>)>,
+    postfix: Vec<(&'p str, u32, Unary<O>)>,
+    parens: Option<(&'p str, &'p str)>,
+    skipper: Option<Skipper>,
+}
+
+/// Start an expression parser over operands parsed by `atom`.
+pub fn expr<'p, O, A>(atom: A) -> Expr<'p, O, A>
+where
+    A: Fn(&'p str) -> PResult<&'p str, O>,
+{
+    Expr {
+        atom,
+        prefix: Vec::new(),
+        infix: Vec::new(),
+        postfix: Vec::new(),
+        parens: None,
+        skipper: None,
+    }
+}
+
+/// Find the longest operator in `ops` that matches the start of `s`.
+fn match_op<'p, 'o, T>(
+    ops: &'o [T],
+    name: impl Fn(&T) -> &'p str,
+    s: &'p str,
+) -> Option<(&'p str, &'o T)> {
+    ops.iter()
+        .filter_map(|op| lit(name(op))(s).ok().map(|(p, l)| (p, l.len(), op)))
+        .max_by_key(|(_, len, _)| *len)
+        .map(|(p, _, op)| (p, op))
+}
+
+impl<'p, O, A> Expr<'p, O, A>
+where
+    A: Fn(&'p str) -> PResult<&'p str, O>,
+{
+    pub fn prefix<F>(mut self, op: &'p str, bp: u8, f: F) -> Self
+    where
+        F: Fn(O) -> O + 'static,
+    {
+        self.prefix.push((op, bp as u32, Box::new(f)));
+        self
+    }
+
+    pub fn infix<F>(mut self, op: &'p str, bp: u8, assoc: Assoc, f: F) -> Self
+    where
+        F: Fn(O, O) -> O + 'static,
+    {
+        self.infix.push((op, bp as u32, assoc, Box::new(f)));
+        self
+    }
+
+    pub fn postfix<F>(mut self, op: &'p str, bp: u8, f: F) -> Self
+    where
+        F: Fn(O) -> O + 'static,
+    {
+        self.postfix.push((op, bp as u32, Box::new(f)));
+        self
+    }
+
+    /// Allow sub-expressions grouped by `open` and `close`.
+    pub fn parens(mut self, open: &'p str, close: &'p str) -> Self {
+        self.parens = Some((open, close));
+        self
+    }
+
+    /// Skip insignificant input around operators and parentheses.
+    pub fn skipper(mut self, skipper: Skipper) -> Self {
+        self.skipper = Some(skipper);
+        self
+    }
+
+    pub fn build(self) -> impl Fn(&'p str) -> PResult<&'p str, O> {
+        return move |s| self.parse_bp(s, 0);
+    }
+
+    fn skip(&self, s: &'p str) -> &'p str {
+        match &self.skipper {
+            Some(sk) => sk.skip(s),
+            None => s,
+        }
+    }
+
+    fn parse_operand(&self, s: &'p str) -> PResult<&'p str, O> {
+        if let Some((p, (_, bp, f))) = match_op(&self.prefix, |op| op.0, s) {
+            let (p, rhs) = self.parse_bp(self.skip(p), bp * 2)?;
+            return Ok((p, f(rhs)));
+        }
+
+        if let Some((open, close)) = self.parens {
+            if let Ok((p, _)) = lit(open)(s) {
+                let (p, inner) = self.parse_bp(self.skip(p), 0)?;
+                let (p, _) = lit(close)(self.skip(p))?;
+                return Ok((p, inner));
+            }
+        }
+
+        (self.atom)(s)
+    }
+
+    fn parse_bp(&self, s: &'p str, min_bp: u32) -> PResult<&'p str, O> {
+        let (mut s, mut lhs) = self.parse_operand(s)?;
+
+        loop {
+            let t = self.skip(s);
+
+            if let Some((p, (_, bp, f))) = match_op(&self.postfix, |op| op.0, t) {
+                if bp * 2 < min_bp {
+                    break;
+                }
+                lhs = f(lhs);
+                s = p;
+                continue;
+            }
+
+            if let Some((p, (_, bp, assoc, f))) = match_op(&self.infix, |op| op.0, t) {
+                let (l_bp, r_bp) = match assoc {
+                    Assoc::Left => (bp * 2, bp * 2 + 1),
+                    Assoc::Right => (bp * 2 + 1, bp * 2),
+                };
+                if l_bp < min_bp {
+                    break;
+                }
+                let (p, rhs) = self.parse_bp(self.skip(p), r_bp)?;
+                lhs = f(lhs, rhs);
+                s = p;
+                continue;
+            }
+
+            break;
+        }
+
+        Ok((s, lhs))
+    }
+}
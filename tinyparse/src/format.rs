@@ -0,0 +1,278 @@
+use crate::{eof, line_ending, ws, ErrorKind, PError, PResult, Parse};
+
+/// A format string split into literal text and `{}` (or `{name}`) holes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Piece {
+    Lit(String),
+    Hole(String),
+}
+
+/// Split `fmt`, where `{{` and `}}` stand for literal braces. Panics if a
+/// brace is unmatched, as a malformed format is a bug rather than bad input.
+pub(crate) fn pieces(fmt: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = fmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut hole = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => hole.push(c),
+                        None => panic!("unclosed placeholder in format {:?}", fmt),
+                    }
+                }
+                if !text.is_empty() {
+                    pieces.push(Piece::Lit(std::mem::take(&mut text)));
+                }
+                pieces.push(Piece::Hole(hole.trim().to_string()));
+            }
+            '}' => panic!("unmatched `}}` in format {:?}", fmt),
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        pieces.push(Piece::Lit(text));
+    }
+    pieces
+}
+
+/// Input being matched against a format string, handed out one hole at a
+/// time. See `FromScan`.
+pub struct Scanner<'a, 'p> {
+    pieces: &'a [Piece],
+    pub(crate) s: &'p str,
+}
+
+impl<'a, 'p> Scanner<'a, 'p> {
+    pub(crate) fn new(pieces: &'a [Piece], s: &'p str) -> Self {
+        Self { pieces, s }
+    }
+
+    /// Match literal text up to the next hole.
+    pub(crate) fn lit(&mut self) -> Result<(), PError> {
+        while let Some((Piece::Lit(l), rest)) = self.pieces.split_first() {
+            match self.s.strip_prefix(l.as_str()) {
+                Some(p) => self.s = p,
+                None => return Err(PError::new(ErrorKind::Expected(l.clone()), self.s)),
+            }
+            self.pieces = rest;
+        }
+        Ok(())
+    }
+
+    /// Name of the next hole, empty for `{}`.
+    pub(crate) fn next_hole(&self) -> Option<&'a str> {
+        self.pieces.iter().find_map(|p| match p {
+            Piece::Hole(h) => Some(h.as_str()),
+            Piece::Lit(_) => None,
+        })
+    }
+
+    /// Step past a hole whose value has been parsed from `s`.
+    pub(crate) fn fill(&mut self) {
+        match self.pieces.split_first() {
+            Some((Piece::Hole(_), rest)) => self.pieces = rest,
+            _ => panic!("format string has fewer fields than the target type"),
+        }
+    }
+
+    /// Match the text before the next hole, then parse its value.
+    pub fn field<T: Parse<'p>>(&mut self) -> Result<T, PError> {
+        self.lit()?;
+        if self.next_hole().is_none() {
+            panic!("format string has fewer fields than the target type");
+        }
+        let (p, v) = T::parse(self.s)?;
+        self.s = p;
+        self.fill();
+        Ok(v)
+    }
+
+    /// Match any text after the last hole.
+    pub(crate) fn finish(mut self) -> PResult<&'p str, ()> {
+        self.lit()?;
+        if !self.pieces.is_empty() {
+            panic!("format string has more fields than the target type");
+        }
+        Ok((self.s, ()))
+    }
+}
+
+/// Types that can be read from the holes of a format string, in order.
+/// Implemented for tuples of `Parse` types.
+pub trait FromScan<'p>: Sized {
+    fn from_scan(sc: &mut Scanner<'_, 'p>) -> Result<Self, PError>;
+}
+
+macro_rules! impl_from_scan {
+    ($($T:ident),+) => {
+        impl<'p, $($T: Parse<'p>),+> FromScan<'p> for ($($T,)+) {
+            fn from_scan(sc: &mut Scanner<'_, 'p>) -> Result<Self, PError> {
+                Ok(($(sc.field::<$T>()?,)+))
+            }
+        }
+    };
+}
+
+impl_from_scan!(T0);
+impl_from_scan!(T0, T1);
+impl_from_scan!(T0, T1, T2);
+impl_from_scan!(T0, T1, T2, T3);
+impl_from_scan!(T0, T1, T2, T3, T4);
+impl_from_scan!(T0, T1, T2, T3, T4, T5);
+impl_from_scan!(T0, T1, T2, T3, T4, T5, T6);
+impl_from_scan!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_from_scan!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_from_scan!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+
+/// Match input against the format string `fmt`, reading the value of each
+/// `{}` hole with `T`'s `FromScan` impl. Text outside holes must match
+/// exactly; `{{` and `}}` match literal braces. This is the runtime
+/// counterpart of `scan!`.
+///
+/// Panics if `fmt` is malformed or has a different number of holes than `T`
+/// has fields.
+pub fn formatted<'p, T: FromScan<'p>>(fmt: &str) -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    let pieces = pieces(fmt);
+    return move |s| {
+        let mut sc = Scanner::new(&pieces, s);
+        let v = T::from_scan(&mut sc)?;
+        let (p, _) = sc.finish()?;
+        Ok((p, v))
+    };
+}
+
+/// Read every line of the input with `formatted(fmt)`. Each line must
+/// match the format completely; trailing blank lines are allowed.
+pub fn from_lines<'p, T: FromScan<'p>>(fmt: &str) -> impl Fn(&'p str) -> PResult<&'p str, Vec<T>> {
+    lines(formatted(fmt))
+}
+
+/// Run `op` over every line of the input.
+pub(crate) fn lines<'p, T, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, Vec<T>>
+where
+    F: Fn(&'p str) -> PResult<&'p str, T>,
+{
+    return move |s| {
+        let mut res: Vec<T> = Vec::new();
+        let mut ss = s;
+
+        loop {
+            let (rest, _) = ws(ss)?;
+            if rest.is_empty() {
+                return Ok((rest, res));
+            }
+
+            let (p, r) = op(ss)?;
+            res.push(r);
+            ss = match line_ending(p) {
+                Ok((p, _)) => p,
+                Err(_) => eof(p).map(|(p, _)| p)?,
+            };
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    #[test]
+    fn test_pieces() {
+        assert_eq!(
+            pieces("p={},{x} {{v}}"),
+            vec![
+                Piece::Lit("p=".to_string()),
+                Piece::Hole("".to_string()),
+                Piece::Lit(",".to_string()),
+                Piece::Hole("x".to_string()),
+                Piece::Lit(" {v}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pieces_unmatched() {
+        pieces("p={");
+    }
+
+    #[test]
+    fn test_formatted() {
+        let robot = formatted::<(i64, i64, i64, i64)>("p={},{} v={},{}");
+        assert_eq!(robot("p=0,4 v=3,-3\n"), Ok(("\n", (0, 4, 3, -3))));
+
+        let e = robot("p=0,4 v=3;-3").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected(",".to_string()));
+
+        let (_, (w, n)) = formatted::<(&str, u8)>("{}: {}")("kz: 2").unwrap();
+        assert_eq!((w, n), ("kz", 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_formatted_arity() {
+        let _ = formatted::<(i64,)>("{},{}")("1,2");
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let src = "5,4\n4,2\n\n";
+        let (p, r) = from_lines::<(i32, i32)>("{},{}")(src).unwrap();
+        assert_eq!(r, vec![(5, 4), (4, 2)]);
+        assert_eq!(p, "");
+
+        let (_, r) = from_lines::<(i32,)>("{}")("1\r\n2\r\n").unwrap();
+        assert_eq!(r, vec![(1,), (2,)]);
+
+        let src = "5,4\n4,2 \n";
+        let e = run(from_lines::<(i32, i32)>("{},{}"), src).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Trailing);
+        assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((2, 4)));
+
+        let src = "5,4\nX+4,2\n";
+        let e = run(from_lines::<(i32, i32)>("{},{}"), src).unwrap_err();
+        assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((2, 1)));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_scan() {
+        use crate::scan;
+
+        let (x, y, vx, vy) = scan!("p={},{} v={},{}", "p=0,4 v=3,-3").unwrap();
+        assert_eq!((x, y, vx, vy), (0i64, 4i64, 3i64, -3i64));
+
+        let n = scan!("Register A: {u32}", "Register A: 729").unwrap();
+        assert_eq!(n, 729);
+
+        let line = String::from("x00 AND y00 -> z00");
+        let (a, op, b, out): (&str, &str, &str, &str) = scan!("{} {} {} -> {}", &line).unwrap();
+        assert_eq!((a, op, b, out), ("x00", "AND", "y00", "z00"));
+
+        let s = "{1}";
+        assert_eq!(scan!("{{{}}}", s), Ok(1u8));
+
+        let e = scan!("X+{i64}", "X-94").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected("X+".to_string()));
+        assert_eq!(e.pos.map(|p| p.offset), Some(0));
+
+        let e = scan!("{i64},{i64}", "1,2,3").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Trailing);
+        assert_eq!(e.pos.map(|p| p.offset), Some(3));
+    }
+}
@@ -0,0 +1,54 @@
+/// Input a parser can run over. Implemented for `&str` and `&[u8]`; the
+/// structural combinators work with either, while text primitives such as
+/// `lit` or `take_while` are `&str`-only and byte primitives such as `tag`
+/// or `take_while_byte` are `&[u8]`-only.
+pub trait Input: Copy {
+    /// Length in bytes.
+    fn input_len(&self) -> usize;
+
+    /// The raw bytes of the input.
+    fn input_bytes(&self) -> &[u8];
+
+    /// Split at byte offset `mid`. For `&str`, `mid` must be on a char
+    /// boundary.
+    fn split_input(self, mid: usize) -> (Self, Self);
+
+    /// Length in bytes of the first char (or byte), 0 if empty.
+    fn unit_len(&self) -> usize;
+}
+
+impl Input for &str {
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+
+    fn input_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn split_input(self, mid: usize) -> (Self, Self) {
+        self.split_at(mid)
+    }
+
+    fn unit_len(&self) -> usize {
+        self.chars().next().map_or(0, char::len_utf8)
+    }
+}
+
+impl Input for &[u8] {
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+
+    fn input_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn split_input(self, mid: usize) -> (Self, Self) {
+        self.split_at(mid)
+    }
+
+    fn unit_len(&self) -> usize {
+        self.len().min(1)
+    }
+}
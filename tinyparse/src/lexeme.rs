@@ -0,0 +1,90 @@
+use crate::{lit, PResult};
+
+/// What counts as insignificant between tokens: spaces and tabs, optionally
+/// newlines, and optionally line comments starting with a given prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Skipper {
+    newlines: bool,
+    comment: Option<&'static str>,
+}
+
+impl Skipper {
+    /// Skip spaces and tabs but stop at newlines, for line-oriented input.
+    pub fn spaces() -> Self {
+        Self {
+            newlines: false,
+            comment: None,
+        }
+    }
+
+    /// Skip spaces, tabs and newlines (`\n` and `\r`).
+    pub fn whitespace() -> Self {
+        Self {
+            newlines: true,
+            comment: None,
+        }
+    }
+
+    /// Also skip comments from `prefix` to the end of the line. The newline
+    /// itself is only skipped if newlines are.
+    pub fn with_comments(mut self, prefix: &'static str) -> Self {
+        self.comment = Some(prefix);
+        self
+    }
+
+    pub fn skip<'p>(&self, mut s: &'p str) -> &'p str {
+        loop {
+            let before = s.len();
+            s = s.trim_start_matches(|c: char| {
+                c == ' ' || c == '\t' || (self.newlines && (c == '\n' || c == '\r'))
+            });
+            if let Some(prefix) = self.comment {
+                if let Some(r) = s.strip_prefix(prefix) {
+                    s = &r[r.find('\n').unwrap_or(r.len())..];
+                }
+            }
+            if s.len() == before {
+                return s;
+            }
+        }
+    }
+
+    /// Parser that skips insignificant input. Never fails.
+    pub fn ws<'p>(self) -> impl Fn(&'p str) -> PResult<&'p str, ()> {
+        return move |s| Ok((self.skip(s), ()));
+    }
+
+    /// Run `op`, then skip any insignificant input after it.
+    pub fn lexeme<'p, O, F>(self, op: F) -> impl Fn(&'p str) -> PResult<&'p str, O>
+    where
+        F: Fn(&'p str) -> PResult<&'p str, O>,
+    {
+        return move |s| {
+            let (p, r) = op(s)?;
+            Ok((self.skip(p), r))
+        };
+    }
+
+    /// Match the literal `sym` as a lexeme.
+    pub fn symbol<'p>(self, sym: &'p str) -> impl Fn(&'p str) -> PResult<&'p str, &'p str> {
+        self.lexeme(lit(sym))
+    }
+}
+
+/// Skip whitespace, including newlines.
+pub fn ws(s: &str) -> PResult<&str, ()> {
+    Skipper::whitespace().ws()(s)
+}
+
+/// Run `op`, then skip whitespace, including newlines.
+pub fn lexeme<'p, O, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, O>
+where
+    F: Fn(&'p str) -> PResult<&'p str, O>,
+{
+    Skipper::whitespace().lexeme(op)
+}
+
+/// Match the literal `sym`, then skip whitespace, including newlines.
+pub fn symbol<'p>(sym: &'p str) -> impl Fn(&'p str) -> PResult<&'p str, &'p str> {
+    Skipper::whitespace().symbol(sym)
+}
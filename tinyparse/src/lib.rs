@@ -1,18 +1,58 @@
-#![feature(iter_advance_by)]
-use anyhow::{anyhow, Error, Result};
-//use thiserror::Error;
+// combinators return their closure with an explicit `return`
+#![allow(clippy::needless_return)]
 
-// #[derive(Error, Debug)]
-// pub enum PError<'p> {
-//     #[error("parse failed")]
-//     ParseFailed(&'p str),
-//     #[error("unknown data store error")]
-//     Unknown,
-// }
+pub mod bytes;
+#[cfg(feature = "serde")]
+pub mod de;
+mod error;
+mod expr;
+mod format;
+mod input;
+mod lexeme;
+mod memo;
+mod num;
+mod parse;
+mod parser;
+mod recover;
+mod span;
+mod stream;
+mod trace;
+mod tuple;
 
-pub type PResult<I, O, E = anyhow::Error> = Result<(I, O), E>;
+pub use bytes::{any_byte, byte, tag, take_while_byte};
+pub use error::{ErrorKind, PError, Position};
+pub use expr::{expr, Assoc, Expr};
+pub use format::{formatted, from_lines, FromScan, Scanner};
+pub use input::Input;
+pub use lexeme::{lexeme, symbol, ws, Skipper};
+pub use memo::{memo, PackratContext};
+pub use num::{bin, float, hex, int, int_n, uint, uint_n, Integer};
+pub use parse::Parse;
+pub use parser::Parser;
+pub use recover::{recover_with, scan_for, skip_until, Scan, Skipped};
+pub use span::{located, Spanned};
+pub use stream::{ParseIter, Stream};
+pub use trace::{trace, trace_capture, trace_depth};
+pub use tuple::{alt, tuple, Alt, Tuple};
 
-fn next_boundary<'p>(s: &'p str) -> usize {
+#[cfg(feature = "derive")]
+pub use tinyparse_derive::{scan, Parse};
+
+// lets derived impls inside this crate name it as `::tinyparse`
+extern crate self as tinyparse;
+
+pub type PResult<I, O, E = PError> = Result<(I, O), E>;
+
+/// Run `p` over `src`, resolving the position of any error against `src`.
+pub fn run<I, O, F>(p: F, src: I) -> PResult<I, O>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    p(src).map_err(|e| e.locate(src))
+}
+
+fn next_boundary(s: &str) -> usize {
     let mut i = 1;
     while i < s.len() {
         if s.is_char_boundary(i) {
@@ -24,50 +64,95 @@ fn next_boundary<'p>(s: &'p str) -> usize {
     s.len()
 }
 
-pub fn peek<'p>(s: &'p str) -> PResult<&'p str, char, Error> {
+pub fn peek(s: &str) -> PResult<&str, char, PError> {
     if s.is_empty() {
-        Err(anyhow!("peek failed, empty str"))
+        Err(PError::new(ErrorKind::Eof, s))
     } else {
         let (c, _) = s.split_at(next_boundary(s));
         Ok((s, c.chars().next().unwrap()))
     }
 }
 
-pub fn next<'p>(s: &'p str) -> PResult<&'p str, char, Error> {
+pub fn next(s: &str) -> PResult<&str, char, PError> {
     if s.is_empty() {
-        Err(anyhow!("peek failed, empty str"))
+        Err(PError::new(ErrorKind::Eof, s))
     } else {
         let (c, p) = s.split_at(next_boundary(s));
         Ok((p, c.chars().next().unwrap()))
     }
 }
 
-pub fn lit<'p>(l: &'p str) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, Error> {
+/// Run `op` without consuming any input.
+pub fn lookahead<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
     return move |s| {
-        let mut source = s.char_indices();
-        let mut lit = l.char_indices();
-        if !s.is_empty()
-            && lit
-                .by_ref()
-                .zip(source.by_ref())
-                .fold(true, |acc, ((_, x), (_, y))| acc & (x == y))
-        {
-            if let Some((i, _)) = source.next() {
-                let (p, r) = s.split_at(i);
-                Ok((r, p))
-            } else {
-                Ok(("", s))
-            }
+        let (_, r) = op(s)?;
+        Ok((s, r))
+    };
+}
+
+/// Succeed without consuming input only if `op` fails.
+pub fn not<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, (), PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    return move |s| match op(s) {
+        Ok(_) => Err(PError::new(ErrorKind::Unexpected, s)),
+        Err(_) => Ok((s, ())),
+    };
+}
+
+pub fn eof<I: Input>(s: I) -> PResult<I, (), PError> {
+    if s.input_len() == 0 {
+        Ok((s, ()))
+    } else {
+        Err(PError::new(ErrorKind::Trailing, s))
+    }
+}
+
+pub fn rest<I: Input>(s: I) -> PResult<I, I, PError> {
+    let (r, p) = s.split_input(s.input_len());
+    Ok((p, r))
+}
+
+/// Run `op` and fail with `ErrorKind::Trailing` if any input is left over.
+pub fn all_consuming<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    left(op, eof)
+}
+
+pub fn satisfy<'p, P>(predicate: P) -> impl Fn(&'p str) -> PResult<&'p str, char, PError>
+where
+    P: Fn(&char) -> bool,
+{
+    return move |s| {
+        let (p, c) = next(s)?;
+        if predicate(&c) {
+            Ok((p, c))
         } else {
-            Err(anyhow!("Failed to match lit"))
+            Err(PError::new(ErrorKind::Predicate, s))
         }
     };
 }
 
+pub fn lit<'p>(l: &'p str) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError> {
+    return move |s| match s.strip_prefix(l) {
+        Some(r) => Ok((r, &s[..l.len()])),
+        None => Err(PError::new(ErrorKind::Expected(l.to_string()), s)),
+    };
+}
+
 pub fn take_max_n_while<'p, P>(
     max: usize,
     predicate: P,
-) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, Error>
+) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError>
 where
     P: Fn(&char) -> bool,
 {
@@ -101,21 +186,22 @@ where
 pub fn take_min_n_while<'p, P>(
     min: usize,
     predicate: P,
-) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, Error>
+) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError>
 where
     P: Fn(&char) -> bool,
 {
     return move |s| {
         let (p, r) = take_while(&predicate)(s)?;
-        if r.len() < min {
-            Err(anyhow!("Failed to take min"))
+        let found = r.chars().count();
+        if found < min {
+            Err(PError::new(ErrorKind::MinCount { min, found }, s))
         } else {
             Ok((p, r))
         }
     };
 }
 
-pub fn take_while<'p, P>(predicate: P) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, Error>
+pub fn take_while<'p, P>(predicate: P) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError>
 where
     P: Fn(&char) -> bool,
 {
@@ -136,31 +222,39 @@ where
     };
 }
 
-pub fn take_until<'p, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, Error>
+/// Take input up to the first offset where `op` matches. `op` is tried at
+/// every char boundary, including the end of input.
+pub fn take_until<'p, O, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError>
 where
-    F: Fn(&'p str) -> PResult<&'p str, &'p str, Error>,
+    F: Fn(&'p str) -> PResult<&'p str, O, PError>,
 {
-    return move |s| loop {
-        let mut ns: &'p str = s;
-        let mut i: usize = 0;
-
-        loop {
-            if let Ok(_) = op(ns) {
+    return move |s| {
+        for i in (0..=s.len()).filter(|i| s.is_char_boundary(*i)) {
+            if op(&s[i..]).is_ok() {
                 let (p, r) = s.split_at(i);
                 return Ok((r, p));
-            } else {
-                if let Ok((p, _)) = next(ns) {
-                    ns = p;
-                    i += 1;
-                } else {
-                    return Err(anyhow!("Failed to parse"));
-                }
             }
         }
+        Err(PError::new(ErrorKind::Eof, &s[s.len()..]))
+    };
+}
+
+/// Take input up to the first occurrence of `l`, using a substring search
+/// rather than retrying a parser at every offset.
+pub fn take_until_lit<'p>(l: &'p str) -> impl Fn(&'p str) -> PResult<&'p str, &'p str, PError> {
+    return move |s| match s.find(l) {
+        Some(i) => {
+            let (p, r) = s.split_at(i);
+            Ok((r, p))
+        }
+        None => Err(PError::new(
+            ErrorKind::Expected(l.to_string()),
+            &s[s.len()..],
+        )),
     };
 }
 
-pub fn skip_while<'p, P>(predicate: &'p P) -> impl Fn(&'p str) -> PResult<&'p str, (), Error>
+pub fn skip_while<'p, P>(predicate: &'p P) -> impl Fn(&'p str) -> PResult<&'p str, (), PError>
 where
     P: Fn(&char) -> bool + 'p,
 {
@@ -170,45 +264,186 @@ where
     };
 }
 
-pub fn left<'p, LO, RO, LF, RF>(lf: LF, rf: RF) -> impl Fn(&'p str) -> PResult<&'p str, LO, Error>
+pub fn left<I, LO, RO, LF, RF>(lf: LF, rf: RF) -> impl Fn(I) -> PResult<I, LO, PError>
 where
-    LF: Fn(&'p str) -> PResult<&'p str, LO, Error>,
-    RF: Fn(&'p str) -> PResult<&'p str, RO, Error>,
+    I: Input,
+    LF: Fn(I) -> PResult<I, LO, PError>,
+    RF: Fn(I) -> PResult<I, RO, PError>,
 {
     return move |s| {
-        if let Ok((p, res)) = lf(s) {
-            if let Ok((pr, _)) = rf(p) {
-                Ok((pr, res))
-            } else {
-                Err(anyhow!("Failed to parse right side"))
-            }
+        let (p, res) = lf(s)?;
+        let (p, _) = rf(p)?;
+        Ok((p, res))
+    };
+}
+
+pub fn right<I, LO, RO, LF, RF>(lf: LF, rf: RF) -> impl Fn(I) -> PResult<I, RO, PError>
+where
+    I: Input,
+    LF: Fn(I) -> PResult<I, LO, PError>,
+    RF: Fn(I) -> PResult<I, RO, PError>,
+{
+    return move |s| {
+        let (p, _) = lf(s)?;
+        rf(p)
+    };
+}
+
+pub fn preceded<I, PO, O, PF, F>(pf: PF, f: F) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    PF: Fn(I) -> PResult<I, PO, PError>,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    right(pf, f)
+}
+
+pub fn terminated<I, O, TO, F, TF>(f: F, tf: TF) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+    TF: Fn(I) -> PResult<I, TO, PError>,
+{
+    left(f, tf)
+}
+
+pub fn delimited<I, OO, O, CO, OF, F, CF>(
+    open: OF,
+    inner: F,
+    close: CF,
+) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    OF: Fn(I) -> PResult<I, OO, PError>,
+    F: Fn(I) -> PResult<I, O, PError>,
+    CF: Fn(I) -> PResult<I, CO, PError>,
+{
+    return move |s| {
+        let (p, _) = open(s)?;
+        let (p, r) = inner(p)?;
+        let (p, _) = close(p)?;
+        Ok((p, r))
+    };
+}
+
+/// Zero or more `item`s separated by `sep`. A trailing separator is left
+/// unconsumed.
+pub fn sep_by<I, O, SO, F, SF>(item: F, sep: SF) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+    SF: Fn(I) -> PResult<I, SO, PError>,
+{
+    return move |s| sep_by1(&item, &sep)(s).or(Ok((s, Vec::new())));
+}
+
+/// One or more `item`s separated by `sep`.
+pub fn sep_by1<I, O, SO, F, SF>(item: F, sep: SF) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+    SF: Fn(I) -> PResult<I, SO, PError>,
+{
+    return move |s| {
+        let (mut ss, r) = item(s)?;
+        let mut res: Vec<O> = vec![r];
+
+        while let Ok((p, r)) = right(&sep, &item)(ss) {
+            res.push(r);
+            ss = p;
+        }
+
+        Ok((ss, res))
+    };
+}
+
+/// A `"\n"` or `"\r\n"` line ending.
+pub fn line_ending<I: Input>(s: I) -> PResult<I, I, PError> {
+    let n = match s.input_bytes() {
+        [b'\n', ..] => 1,
+        [b'\r', b'\n', ..] => 2,
+        _ => return Err(PError::new(ErrorKind::Expected("\n".to_string()), s)),
+    };
+    let (p, r) = s.split_input(n);
+    Ok((r, p))
+}
+
+/// Whether `line` is blank: nothing but spaces, tabs and its line ending.
+pub fn is_blank_line(line: &[u8]) -> bool {
+    line.iter()
+        .all(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+/// Byte ranges of the blocks of `src` separated by one or more blank lines
+/// (see `is_blank_line`), without their final line ending.
+pub fn block_ranges<I: Input>(src: I) -> Vec<(usize, usize)> {
+    let mut block: Option<(usize, usize)> = None;
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+
+    for line in src.input_bytes().split_inclusive(|b| *b == b'\n') {
+        if is_blank_line(line) {
+            ranges.extend(block.take());
         } else {
-            Err(anyhow!("Failed to parse left side"))
+            let len = line.len()
+                - line
+                    .iter()
+                    .rev()
+                    .take_while(|b| matches!(b, b'\n' | b'\r'))
+                    .count();
+            block = Some((block.map_or(start, |(b, _)| b), start + len));
+        }
+        start += line.len();
+    }
+    ranges.extend(block);
+    ranges
+}
+
+/// Split the input into blocks separated by one or more blank lines and run
+/// `op` over each one; `op` must consume its whole block, which excludes the
+/// final line ending. Leading and trailing blank lines are skipped, and the
+/// whole input is consumed. Errors point into the original input.
+pub fn blocks<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    return move |s| {
+        let mut res: Vec<O> = Vec::new();
+        for (b, e) in block_ranges(s) {
+            let blk = s.split_input(e).0.split_input(b).1;
+            let (_, r) = all_consuming(&op)(blk).map_err(|err| err.shift(s.input_len() - e))?;
+            res.push(r);
         }
+
+        Ok((s.split_input(s.input_len()).1, res))
     };
 }
 
-pub fn right<'p, LO, RO, LF, RF>(lf: LF, rf: RF) -> impl Fn(&'p str) -> PResult<&'p str, RO, Error>
+/// Exactly `n` repetitions of `op`.
+pub fn count<I, O, F>(n: usize, op: F) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
 where
-    LF: Fn(&'p str) -> PResult<&'p str, LO, Error>,
-    RF: Fn(&'p str) -> PResult<&'p str, RO, Error>,
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
 {
     return move |s| {
-        if let Ok((p, _)) = lf(s) {
-            if let Ok((p, r)) = rf(p) {
-                Ok((p, r))
-            } else {
-                Err(anyhow!("Failed to parse right"))
-            }
-        } else {
-            Err(anyhow!("Failed to parse left"))
+        let mut res: Vec<O> = Vec::with_capacity(n);
+        let mut ss = s;
+
+        for _ in 0..n {
+            let (p, r) = op(ss)?;
+            res.push(r);
+            ss = p;
         }
+
+        Ok((ss, res))
     };
 }
 
-pub fn many0<'p, O, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, Vec<O>, Error>
+pub fn many0<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
 where
-    F: Fn(&'p str) -> PResult<&'p str, O, Error>,
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
 {
     return move |s| {
         let mut res: Vec<O> = Vec::new();
@@ -223,33 +458,100 @@ where
     };
 }
 
-pub fn many1<'p, O, F>(op: F) -> impl Fn(&'p str) -> PResult<&'p str, Vec<O>, Error>
+pub fn many1<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, Vec<O>, PError>
 where
-    F: Fn(&'p str) -> PResult<&'p str, O, Error>,
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
 {
     return move |s| {
         let mut res: Vec<O> = Vec::new();
         let mut ss = s;
 
-        if let Ok((p, r)) = op(ss) {
-            res.push(r);
-            ss = p;
+        let (p, r) = op(ss)?;
+        res.push(r);
+        ss = p;
 
-            if let Ok((p, mut vs)) = many0(&op)(ss) {
-                res.append(&mut vs);
-                Ok((p, res))
-            } else {
-                Ok((ss, res))
-            }
+        if let Ok((p, mut vs)) = many0(&op)(ss) {
+            res.append(&mut vs);
+            Ok((p, res))
         } else {
-            Err(anyhow!("Failed to parse"))
+            Ok((ss, res))
         }
     };
 }
 
-pub fn opt<'p, O, F>(fp: F) -> impl Fn(&'p str) -> PResult<&'p str, O, Error>
+/// Like `many0`, but fold each match into an accumulator starting from
+/// `init` instead of collecting them.
+pub fn many0_fold<I, O, A, F, G>(op: F, init: A, f: G) -> impl Fn(I) -> PResult<I, A, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+    A: Clone,
+    G: Fn(A, O) -> A,
+{
+    return move |s| {
+        let mut it = iterate(&op, s);
+        let acc = it.by_ref().fold(init.clone(), &f);
+        Ok((it.remaining(), acc))
+    };
+}
+
+/// Lazily run `op` over `input` as many times as it matches. Iteration
+/// stops at the first failure, or at a match that consumes nothing.
+pub fn iterate<I, O, F>(op: F, input: I) -> Iterate<I, F>
 where
-    F: Fn(&'p str) -> PResult<&'p str, O, Error>,
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    Iterate {
+        op,
+        input,
+        done: false,
+    }
+}
+
+/// Iterator over the matches of a parser; see `iterate`.
+pub struct Iterate<I, F> {
+    op: F,
+    input: I,
+    done: bool,
+}
+
+impl<I: Input, F> Iterate<I, F> {
+    /// The input after the last match.
+    pub fn remaining(&self) -> I {
+        self.input
+    }
+}
+
+impl<I, O, F> Iterator for Iterate<I, F>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        if self.done {
+            return None;
+        }
+        match (self.op)(self.input) {
+            Ok((p, o)) if p.input_len() < self.input.input_len() => {
+                self.input = p;
+                Some(o)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+pub fn opt<I, O, F>(fp: F) -> impl Fn(I) -> PResult<I, O, PError>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
     O: std::default::Default,
 {
     return move |s| fp(s).or(Ok((s, Default::default())));
@@ -258,8 +560,7 @@ where
 #[macro_export]
 macro_rules! seq {
     ($src:ident => $($ops:expr), +) => {
-        //fn __f<'p>(__s: &'p str) -> PResult<&'p str, (), Error> {
-        (|| -> PResult<&str, (), Error> {
+        (|| -> $crate::PResult<_, ()> {
             let __s = $src;
             $( let (__s, _) = $ops(__s)?; )*
             Ok((__s, ()))
@@ -267,18 +568,20 @@ macro_rules! seq {
     };
 }
 
-pub fn one_of<'p, O, F>(ops: Vec<F>) -> impl Fn(&'p str) -> PResult<&'p str, O, Error>
+pub fn one_of<I, O, F>(ops: Vec<F>) -> impl Fn(I) -> PResult<I, O, PError>
 where
-    F: Fn(&'p str) -> PResult<&'p str, O, Error>,
+    I: Input,
+    F: Fn(I) -> PResult<I, O, PError>,
 {
     return move |s| {
+        let mut errs = Vec::with_capacity(ops.len());
         for op in &ops {
-            let v = op(s);
-            if v.is_ok() {
-                return v;
+            match op(s) {
+                Ok(v) => return Ok(v),
+                Err(e) => errs.push(e),
             }
         }
-        Err(anyhow!("Failed to parse one_of"))
+        Err(PError::new(ErrorKind::Alt(errs), s))
     };
 }
 
@@ -324,6 +627,51 @@ mod tests {
         assert_eq!(p, "test");
     }
 
+    #[test]
+    fn test_lookahead() {
+        let src = "test";
+        let (p, c) = lookahead(lit("te"))(src).unwrap();
+        assert_eq!(c, "te");
+        assert_eq!(p, "test");
+
+        assert!(lookahead(lit("st"))(src).is_err());
+    }
+
+    #[test]
+    fn test_not() {
+        let (p, _) = not(lit("don't"))("do()").unwrap();
+        assert_eq!(p, "do()");
+
+        let e = not(lit("don't"))("don't()").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Unexpected);
+
+        let word = right(
+            not(lit("->")),
+            take_min_n_while(1, |c: &char| !c.is_whitespace()),
+        );
+        assert_eq!(word("x00 -> z00").unwrap(), (" -> z00", "x00"));
+        assert!(word("-> z00").is_err());
+    }
+
+    #[test]
+    fn test_eof_rest() {
+        assert_eq!(eof("").unwrap(), ("", ()));
+        assert_eq!(eof("x").unwrap_err().kind, ErrorKind::Trailing);
+
+        assert_eq!(rest("abc").unwrap(), ("", "abc"));
+        assert_eq!(rest("").unwrap(), ("", ""));
+    }
+
+    #[test]
+    fn test_all_consuming() {
+        let nums = all_consuming(sep_by1(uint::<u32>(), lit(",")));
+        assert_eq!(nums("1,2,3").unwrap(), ("", vec![1, 2, 3]));
+
+        let e = run(&nums, "1,2,3x").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Trailing);
+        assert_eq!(e.pos.unwrap().offset, 5);
+    }
+
     #[test]
     fn test_next() {
         let src = "test";
@@ -358,6 +706,14 @@ mod tests {
         assert_eq!(s, "1234");
     }
 
+    #[test]
+    fn test_skip_while() {
+        let digit = |c: &char| c.is_ascii_digit();
+        assert_eq!(skip_while(&digit)("1234捒a"), Ok(("捒a", ())));
+        assert_eq!(skip_while(&digit)("捒a"), Ok(("捒a", ())));
+        assert_eq!(skip_while(&digit)("12"), Ok(("", ())));
+    }
+
     #[test]
     fn test_take_max_n_while() {
         let src = "123";
@@ -428,6 +784,30 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_many0_fold() {
+        let src = "1,2,3,x";
+        let sum = many0_fold(terminated(uint::<u32>(), lit(",")), 0, |a, n| a + n);
+        assert_eq!(sum(src), Ok(("x", 6)));
+        assert_eq!(sum("x"), Ok(("x", 0)));
+
+        let count = many0_fold(bytes::tag(b"ab"), 0, |n, _| n + 1);
+        assert_eq!(count(&b"ababa"[..]), Ok((&b"a"[..], 2)));
+    }
+
+    #[test]
+    fn test_iterate() {
+        let mut it = iterate(terminated(uint::<u32>(), opt(lit(","))), "1,2,3;4");
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(it.remaining(), ";4");
+        assert_eq!(it.next(), None);
+
+        // a match that consumes nothing ends the iteration
+        let mut it = iterate(opt(lit("a")), "aab");
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!(it.remaining(), "b");
+    }
+
     #[test]
     fn test_one_of() {
         let src = "foobar";
@@ -449,6 +829,506 @@ mod tests {
         assert_eq!(s, "barbarfoo");
     }
 
+    #[test]
+    fn test_take_until_multibyte() {
+        let src = "捒捒foo\r\nbar";
+        let (p, s) = take_until(lit("\r\n"))(src).unwrap();
+        assert_eq!(s, "捒捒foo");
+        assert_eq!(p, "\r\nbar");
+
+        let (p, s) = take_until(satisfy(|c: &char| c.is_ascii_digit()))("捒a捒1").unwrap();
+        assert_eq!(s, "捒a捒");
+        assert_eq!(p, "1");
+
+        let (p, s) = take_until(eof)("捒a").unwrap();
+        assert_eq!(s, "捒a");
+        assert_eq!(p, "");
+
+        let e = run(take_until(lit("\n")), "捒a").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Eof);
+        assert_eq!(e.pos.unwrap().offset, 4);
+    }
+
+    #[test]
+    fn test_take_until_lit() {
+        let src = "barbar捒foo\r\n";
+        let (p, s) = take_until_lit("\r\n")(src).unwrap();
+        assert_eq!(p, "\r\n");
+        assert_eq!(s, "barbar捒foo");
+
+        let (p, s) = take_until_lit("mul(")("mul(1,2)").unwrap();
+        assert_eq!(p, "mul(1,2)");
+        assert_eq!(s, "");
+
+        let e = take_until_lit("\n")("no newline").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected("\n".to_string()));
+    }
+
+    #[test]
+    fn test_take_until_large_input() {
+        let src = "捒x".repeat(1 << 20) + "END";
+        let (p, s) = take_until_lit("END")(&src).unwrap();
+        assert_eq!(p, "END");
+        assert_eq!(s.len(), 4 << 20);
+
+        let (p, s) = take_until(lit("END"))(&src).unwrap();
+        assert_eq!(p, "END");
+        assert_eq!(s.len(), 4 << 20);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let src: &[u8] = b"mul(2,4)x";
+        let (p, r) = tag(b"mul(")(src).unwrap();
+        assert_eq!(r, b"mul(");
+        assert_eq!(p, b"2,4)x");
+
+        let (p, r) = byte(b'2')(p).unwrap();
+        assert_eq!(r, b'2');
+        assert_eq!(p, b",4)x");
+
+        let (p, r) = take_while_byte(|b: &u8| *b != b')')(p).unwrap();
+        assert_eq!(r, b",4");
+        assert_eq!(p, b")x");
+
+        let (p, r) = any_byte(p).unwrap();
+        assert_eq!(r, b')');
+        assert_eq!(p, b"x");
+
+        assert_eq!(any_byte(b"").unwrap_err().kind, ErrorKind::Eof);
+        assert_eq!(tag(b"do")(b"don't").unwrap(), (&b"n't"[..], &b"do"[..]));
+        assert!(tag(b"don't")(b"do()").is_err());
+    }
+
+    #[test]
+    fn test_bytes_combinators() {
+        let mul = delimited(
+            tag(b"mul("),
+            tuple((
+                bytes::uint_n::<i32>(1, 3),
+                preceded(byte(b','), bytes::uint_n::<i32>(1, 3)),
+            )),
+            byte(b')'),
+        );
+        assert_eq!(mul(b"mul(11,8)?").unwrap(), (&b"?"[..], (11, 8)));
+
+        let disk = all_consuming(many1(bytes::uint_n::<u8>(1, 1)));
+        assert_eq!(disk(b"2333133121414131402").unwrap().1.len(), 19);
+
+        let nums = sep_by1(bytes::int::<i64>(), tag(b", "));
+        assert_eq!(nums(b"-3, 4, +5").unwrap(), (&b""[..], vec![-3, 4, 5]));
+        assert_eq!(bytes::float()(b"-84.25").unwrap(), (&b""[..], -84.25));
+        assert_eq!(bytes::hex::<u8>()(b"ff").unwrap(), (&b""[..], 255));
+        assert_eq!(bytes::bin::<u8>()(b"1012").unwrap(), (&b"2"[..], 0b101));
+        assert_eq!(bytes::int_n::<i8>(1, 2)(b"-123").unwrap(), (&b"3"[..], -12));
+
+        let src: &[u8] = b"1,2\n3;4";
+        let e = run(
+            all_consuming(sep_by1(
+                sep_by1(bytes::uint::<u8>(), byte(b',')),
+                byte(b'\n'),
+            )),
+            src,
+        )
+        .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Trailing);
+        assert_eq!(
+            e.pos,
+            Some(Position {
+                offset: 5,
+                line: 2,
+                col: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_located() {
+        let src = "xmul(2,4)%&\nmul[3,7]!mul(11,8)";
+        let mul = located(
+            src,
+            delimited(
+                lit("mul("),
+                tuple((uint::<i32>(), preceded(lit(","), uint::<i32>()))),
+                lit(")"),
+            ),
+        );
+        let (p, _) = take_until_lit("mul(")(src).unwrap();
+        let (p, m) = mul(p).unwrap();
+        assert_eq!(
+            m,
+            Spanned {
+                value: (2, 4),
+                start: 1,
+                end: 9,
+                line: 1,
+                col: 2
+            }
+        );
+        assert_eq!(&src[m.start..m.end], "mul(2,4)");
+
+        let (p, _) = take_until_lit("mul(")(p).unwrap();
+        let (p, m) = mul(p).unwrap();
+        assert_eq!(p, "");
+        assert_eq!((m.line, m.col, m.len()), (2, 10, 9));
+        assert_eq!(m.map(|(a, b)| a * b).value, 88);
+
+        // going back to an earlier match recounts from the start
+        let (_, m) = mul(&src[1..]).unwrap();
+        assert_eq!((m.start, m.line, m.col), (1, 1, 2));
+
+        // input that is not a suffix of src is rejected, not miscounted
+        let e = mul("mul(1,1)").unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Invalid(_)));
+    }
+
+    #[test]
+    fn test_ws() {
+        assert_eq!(ws(" \t\n\r\nx ").unwrap(), ("x ", ()));
+        assert_eq!(ws("x").unwrap(), ("x", ()));
+
+        let sp = Skipper::spaces();
+        assert_eq!(sp.ws()("  \nx").unwrap(), ("\nx", ()));
+
+        let sc = Skipper::whitespace().with_comments("#");
+        assert_eq!(sc.ws()("  # note\n\n# more\n  x").unwrap(), ("x", ()));
+
+        let sc = Skipper::spaces().with_comments("//");
+        assert_eq!(sc.ws()("  // note\nx").unwrap(), ("\nx", ()));
+    }
+
+    #[test]
+    fn test_lexeme() {
+        #[derive(Debug, PartialEq)]
+        enum Op {
+            And,
+            Or,
+            Xor,
+        }
+
+        let name = || lexeme(take_min_n_while(1, |c: &char| c.is_ascii_alphanumeric()));
+        let op = alt((
+            symbol("AND").map(|_| Op::And),
+            symbol("XOR").map(|_| Op::Xor),
+            symbol("OR").map(|_| Op::Or),
+        ));
+        let gate = tuple((name(), op, name(), preceded(symbol("->"), name())));
+
+        let src = "x00 AND y00 -> z00\nx01   XOR y01->z01\n";
+        let (_, gates) = run(all_consuming(preceded(ws, many1(gate))), src).unwrap();
+        assert_eq!(
+            gates,
+            vec![
+                ("x00", Op::And, "y00", "z00"),
+                ("x01", Op::Xor, "y01", "z01")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skipper_lexeme() {
+        let sp = Skipper::spaces().with_comments("#");
+        let equation = tuple((
+            terminated(uint::<i64>(), sp.symbol(":")),
+            many1(sp.lexeme(uint::<i64>())),
+        ));
+        let lines = sep_by1(equation, lit("\n"));
+
+        let src = "190: 10 19 # comment\n3267:  81 40\t27";
+        let (p, eqs) = lines(src).unwrap();
+        assert_eq!(p, "");
+        assert_eq!(eqs, vec![(190, vec![10, 19]), (3267, vec![81, 40, 27])]);
+    }
+
+    #[test]
+    fn test_expr_fold() {
+        let calc = expr(int::<i64>())
+            .infix("+", 1, Assoc::Left, |a, b| a + b)
+            .infix("-", 1, Assoc::Left, |a, b| a - b)
+            .infix("*", 2, Assoc::Left, |a, b| a * b)
+            .infix("^", 3, Assoc::Right, |a: i64, b| a.pow(b as u32))
+            .prefix("~", 4, |a| -a)
+            .postfix("!", 5, |a| (1..=a).product())
+            .parens("(", ")")
+            .build();
+
+        assert_eq!(calc("1+2*3").unwrap(), ("", 7));
+        assert_eq!(calc("10-4-3").unwrap(), ("", 3));
+        assert_eq!(calc("2^3^2").unwrap(), ("", 512));
+        assert_eq!(calc("~2^2").unwrap(), ("", 4));
+        assert_eq!(calc("~3!").unwrap(), ("", -6));
+        assert_eq!(calc("(1+2)*3!").unwrap(), ("", 18));
+        assert_eq!(
+            calc("2*(3").unwrap_err().kind,
+            ErrorKind::Expected(")".to_string())
+        );
+        assert_eq!(calc("1+2;").unwrap(), (";", 3));
+        assert!(calc("*1").is_err());
+    }
+
+    #[test]
+    fn test_expr_ast() {
+        #[derive(Debug, PartialEq)]
+        enum Ast {
+            Var(String),
+            Not(Box<Ast>),
+            Bin(&'static str, Box<Ast>, Box<Ast>),
+        }
+
+        let bin = |op: &'static str| move |a, b| Ast::Bin(op, Box::new(a), Box::new(b));
+        let formula = expr(
+            take_min_n_while(1, |c: &char| c.is_ascii_lowercase() || c.is_ascii_digit())
+                .map(|v| Ast::Var(v.to_string())),
+        )
+        .infix("OR", 1, Assoc::Left, bin("OR"))
+        .infix("XOR", 2, Assoc::Left, bin("XOR"))
+        .infix("AND", 3, Assoc::Left, bin("AND"))
+        .prefix("NOT", 4, |a| Ast::Not(Box::new(a)))
+        .parens("(", ")")
+        .skipper(Skipper::spaces())
+        .build();
+
+        let v = |n: &str| Box::new(Ast::Var(n.to_string()));
+        let (p, ast) = formula("x00 XOR y00 AND NOT c OR ( z01 )\n").unwrap();
+        assert_eq!(p, "\n");
+        assert_eq!(
+            ast,
+            Ast::Bin(
+                "OR",
+                Box::new(Ast::Bin(
+                    "XOR",
+                    v("x00"),
+                    Box::new(Ast::Bin("AND", v("y00"), Box::new(Ast::Not(v("c")))))
+                )),
+                v("z01")
+            )
+        );
+    }
+
+    // PEG: s := "a" s "b" / "a" s "c" / "a", returning the nesting depth.
+    // On "aaa...ccc" every level parses its inner `s` for the first
+    // alternative, fails on "b", and parses it again for the second.
+    fn nested<'p>(
+        ctx: Option<&PackratContext>,
+        calls: &std::cell::Cell<usize>,
+        s: &'p str,
+    ) -> PResult<&'p str, usize> {
+        let body = |s: &'p str| {
+            calls.set(calls.get() + 1);
+            let inner = |s| nested(ctx, calls, s);
+            alt((
+                delimited(lit("a"), inner, lit("b")).map(|d| d + 1),
+                delimited(lit("a"), inner, lit("c")).map(|d| d + 1),
+                lit("a").value(1),
+            ))(s)
+        };
+        match ctx {
+            Some(ctx) => ctx.apply("nested", s, body),
+            None => body(s),
+        }
+    }
+
+    #[test]
+    fn test_packrat_calls() {
+        let n = 18;
+        let src = "a".repeat(n) + &"c".repeat(n - 1);
+
+        // without memoization the shared prefix is reparsed on every branch
+        let calls = std::cell::Cell::new(0);
+        let (p, depth) = nested(None, &calls, &src).unwrap();
+        assert_eq!((p, depth), ("", n));
+        assert!(calls.get() >= 1 << (n - 1));
+
+        // with it, each offset is parsed once
+        let ctx = PackratContext::new();
+        let calls = std::cell::Cell::new(0);
+        let (p, depth) = nested(Some(&ctx), &calls, &src).unwrap();
+        assert_eq!((p, depth), ("", n));
+        assert_eq!(calls.get(), n + 1);
+        assert!(ctx.hits() >= n - 1);
+    }
+
+    #[test]
+    fn test_packrat_context_memo() {
+        let ctx = PackratContext::new();
+        let calls = std::cell::Cell::new(0);
+        let num = ctx.memo(|s| {
+            calls.set(calls.get() + 1);
+            uint::<u32>()(s)
+        });
+        let p = alt((terminated(&num, lit("+")), terminated(&num, lit("-")), &num));
+
+        assert_eq!(p("12-").unwrap(), ("", 12));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(ctx.hits(), 1);
+
+        // errors are cached too
+        assert!(p("x").is_err());
+        assert_eq!(calls.get(), 2);
+
+        ctx.clear();
+        assert_eq!(p("7").unwrap(), ("", 7));
+        assert_eq!(calls.get(), 3);
+
+        // inputs of the same length do not share entries
+        let (a, b) = "34+56-".split_at(3);
+        assert_eq!(p(a).unwrap(), ("", 34));
+        assert_eq!(p(b).unwrap(), ("", 56));
+        assert_eq!(calls.get(), 5);
+        assert_eq!(p(a).unwrap(), ("", 34));
+        assert_eq!(calls.get(), 5);
+    }
+
+    #[test]
+    fn test_memo() {
+        let calls = std::cell::Cell::new(0);
+        let word = memo(|s| {
+            calls.set(calls.get() + 1);
+            take_min_n_while(1, |c: &char| c.is_alphabetic())(s)
+        });
+        let p = alt((terminated(&word, lit("!")), terminated(&word, lit("?"))));
+
+        assert_eq!(p("hey?").unwrap(), ("", "hey"));
+        assert_eq!(calls.get(), 1);
+
+        let (a, b) = "abc!xyz!".split_at(4);
+        assert_eq!(p(a).unwrap(), ("", "abc"));
+        assert_eq!(p(b).unwrap(), ("", "xyz"));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_skip_until() {
+        assert_eq!(skip_until(lit("\n"))("ab捒\ncd").unwrap(), ("\ncd", "ab捒"));
+        assert_eq!(skip_until(lit("\n"))("abcd").unwrap(), ("", "abcd"));
+        assert_eq!(skip_until(lit("\n"))("\n").unwrap(), ("\n", ""));
+    }
+
+    #[test]
+    fn test_recover_with() {
+        let src = "1\n2x\n-3\nfoo\n5";
+        // a number followed by the end of the line
+        let item = terminated(int::<i32>(), not(satisfy(|c: &char| *c != '\n')));
+        let line = terminated(recover_with(item, skip_until(lit("\n"))), opt(lit("\n")));
+        let (p, rs) = many0(line)(src).unwrap();
+        assert_eq!(p, "");
+
+        let items: Vec<i32> = rs.iter().filter_map(|r| r.as_ref().ok().copied()).collect();
+        assert_eq!(items, vec![1, -3, 5]);
+
+        let skipped: Vec<_> = rs.into_iter().filter_map(|r| r.err()).collect();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].input, "2x");
+        assert_eq!(skipped[1].input, "foo");
+        let e = skipped[1].error.clone().locate(src);
+        assert_eq!((e.pos.unwrap().line, e.pos.unwrap().col), (4, 1));
+
+        // recovery that consumes nothing still makes progress
+        let (p, r) = recover_with(lit("a"), skip_until(lit("\n")))("\nb").unwrap();
+        assert_eq!(p, "b");
+        assert_eq!(r.unwrap_err().input, "\n");
+        assert!(recover_with(lit("a"), skip_until(lit("\n")))("").is_err());
+    }
+
+    #[test]
+    fn test_scan_for() {
+        let src = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        let mul = delimited(
+            lit("mul("),
+            tuple((uint_n::<i32>(1, 3), preceded(lit(","), uint_n::<i32>(1, 3)))),
+            lit(")"),
+        );
+        let (p, scan) = scan_for(mul)(src).unwrap();
+        assert_eq!(p, "");
+        assert_eq!(scan.items, vec![(2, 4), (5, 5), (11, 8), (8, 5)]);
+        assert_eq!(
+            scan.skipped.iter().map(|s| s.input).collect::<Vec<_>>(),
+            vec!["x", "%&mul[3,7]!@^do_not_", "+mul(32,64]then(", ")"]
+        );
+        assert_eq!(
+            scan.skipped[0].error.kind,
+            ErrorKind::Expected("mul(".to_string())
+        );
+        assert_eq!(scan.skipped[3].error.offset(src), src.len() - 1);
+
+        let (_, scan) = scan_for(bytes::uint::<u8>())(&b"a1b22c"[..]).unwrap();
+        assert_eq!(scan.items, vec![1, 22]);
+        assert_eq!(scan.skipped.len(), 3);
+    }
+
+    #[test]
+    fn test_trace() {
+        let num = || trace("num", uint_n::<i32>(1, 3));
+        let mul = trace(
+            "mul",
+            left(
+                right(trace("open", lit("mul(")), num()),
+                trace("comma", lit(",")),
+            ),
+        );
+        let list = trace("list", many0(trace("item", right(opt(lit(" ")), mul))));
+
+        let (res, lines) = trace_capture(|| list("mul(1, mul(22,mul(3;"));
+        let (p, r) = res.unwrap();
+        assert_eq!(r, vec![1, 22]);
+        assert_eq!(p, "mul(3;");
+        assert_eq!(trace_depth(), 0);
+        if cfg!(feature = "trace") {
+            assert_eq!(lines.len(), 32);
+            assert_eq!(
+                lines[21..],
+                [
+                    "  > item @14",
+                    "    > mul @14",
+                    "      > open @14",
+                    "      < open @14 ok +4",
+                    "      > num @18",
+                    "      < num @18 ok +1",
+                    "      > comma @19",
+                    "      < comma @19 err: expected \",\"",
+                    "    < mul @14 err: expected \",\"",
+                    "  < item @14 err: expected \",\"",
+                    "< list @0 ok +14",
+                ]
+            );
+        } else {
+            assert!(lines.is_empty());
+        }
+
+        let (res, lines) = trace_capture(|| run(trace("top", all_consuming(list)), "mul(1,x"));
+        assert_eq!(res.unwrap_err().kind, ErrorKind::Trailing);
+        assert_eq!(trace_depth(), 0);
+        if cfg!(feature = "trace") {
+            assert_eq!(
+                lines,
+                [
+                    "> top @0",
+                    "  > list @0",
+                    "    > item @0",
+                    "      > mul @0",
+                    "        > open @0",
+                    "        < open @0 ok +4",
+                    "        > num @4",
+                    "        < num @4 ok +1",
+                    "        > comma @5",
+                    "        < comma @5 ok +1",
+                    "      < mul @0 ok +6",
+                    "    < item @0 ok +6",
+                    "    > item @6",
+                    "      > mul @6",
+                    "        > open @6",
+                    "        < open @6 err: expected \"mul(\"",
+                    "      < mul @6 err: expected \"mul(\"",
+                    "    < item @6 err: expected \"mul(\"",
+                    "  < list @0 ok +6",
+                    "< top @0 err: expected end of input",
+                ]
+            );
+        } else {
+            assert!(lines.is_empty());
+        }
+    }
+
     #[test]
     fn test_parse_data_field() {
         let src = "data: foofoo\r\nbarbar";
@@ -457,6 +1337,314 @@ mod tests {
         println!("{} - {}: {}", p, f, d.trim());
     }
 
+    #[test]
+    fn test_satisfy() {
+        let (p, c) = satisfy(|c: &char| c.is_ascii_digit())("1a").unwrap();
+        assert_eq!(c, '1');
+        assert_eq!(p, "a");
+
+        let e = satisfy(|c: &char| c.is_ascii_digit())("a1").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Predicate);
+    }
+
+    #[test]
+    fn test_errors() {
+        let src = "foo\nbarbaz";
+        let e = run(right(lit("foo\n"), right(lit("bar"), lit("qux"))), src).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected("qux".to_string()));
+        assert_eq!(
+            e.pos,
+            Some(Position {
+                offset: 7,
+                line: 2,
+                col: 4
+            })
+        );
+        assert_eq!(
+            e.to_string(),
+            "expected \"qux\" at line 2, column 4 (byte 7)"
+        );
+
+        let e = take_min_n_while(3, |c: &char| c.is_ascii_digit())("12a").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::MinCount { min: 3, found: 2 });
+
+        let e = next("").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Eof);
+
+        let src = "捒baz";
+        let e = run(right(next, one_of!(lit("foo"), lit("bar"))), src).unwrap_err();
+        assert!(matches!(&e.kind, ErrorKind::Alt(es) if es.len() == 2));
+        assert_eq!(e.pos.unwrap().offset, 3);
+        assert_eq!(e.pos.unwrap().col, 2);
+    }
+
+    #[test]
+    fn test_seq_error() {
+        let src = "foo,bar;baz";
+        let e = seq!(src => lit("foo,"), lit("bar,"), lit("baz"))
+            .unwrap_err()
+            .locate(src);
+        assert_eq!(e.offset(src), 4);
+        assert_eq!(e.kind, ErrorKind::Expected("bar,".to_string()));
+    }
+
+    #[test]
+    fn test_parser_map() {
+        let digits = || take_min_n_while(1, |c: &char| c.is_ascii_digit());
+
+        let (p, n) = digits().map(|d| d.len()).parse("1234a").unwrap();
+        assert_eq!(n, 4);
+        assert_eq!(p, "a");
+
+        let (p, n) = digits().map_res(|d| d.parse::<u8>()).parse("42,").unwrap();
+        assert_eq!(n, 42);
+        assert_eq!(p, ",");
+
+        let e = digits()
+            .map_res(|d| d.parse::<u8>())
+            .parse("420")
+            .unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Invalid(_)));
+    }
+
+    #[test]
+    fn test_parser_and_then() {
+        // A length-prefixed field: "3:abcdef" -> "abc"
+        let field = take_min_n_while(1, |c: &char| c.is_ascii_digit())
+            .map_res(|d| d.parse::<usize>())
+            .skip(lit(":"))
+            .and_then(|n| take_max_n_while(n, |_: &char| true));
+        let (p, r) = field.parse("3:abcdef").unwrap();
+        assert_eq!(r, "abc");
+        assert_eq!(p, "def");
+    }
+
+    #[test]
+    fn test_parser_filter_or_value() {
+        let even = take_min_n_while(1, |c: &char| c.is_ascii_digit())
+            .map_res(|d| d.parse::<i32>())
+            .filter(|n| n % 2 == 0);
+        assert_eq!(even.parse("12").unwrap(), ("", 12));
+        assert_eq!(even.parse("13").unwrap_err().kind, ErrorKind::Predicate);
+
+        let b = lit("yes").value(true).or(lit("no").value(false));
+        assert_eq!(b.parse("yes!").unwrap(), ("!", true));
+        assert_eq!(b.parse("no!").unwrap(), ("!", false));
+        assert!(matches!(b.parse("maybe").unwrap_err().kind, ErrorKind::Alt(es) if es.len() == 2));
+    }
+
+    #[test]
+    fn test_parser_then_skip() {
+        let num =
+            || take_min_n_while(1, |c: &char| c.is_ascii_digit()).map_res(|d| d.parse::<i32>());
+        let pair = right(lit("mul("), num())
+            .skip(lit(","))
+            .then(num())
+            .skip(lit(")"));
+        assert_eq!(pair.parse("mul(2,4)x").unwrap(), ("x", (2, 4)));
+        assert!(pair.parse("mul(2,4]").is_err());
+    }
+
+    #[test]
+    fn test_uint() {
+        assert_eq!(uint::<u32>()("1234,5").unwrap(), (",5", 1234));
+        assert_eq!(uint::<u8>()("255").unwrap(), ("", 255));
+        assert!(matches!(
+            uint::<u8>()("256").unwrap_err().kind,
+            ErrorKind::Invalid(_)
+        ));
+        assert_eq!(
+            uint::<u8>()("-1").unwrap_err().kind,
+            ErrorKind::MinCount { min: 1, found: 0 }
+        );
+        assert!(uint::<u8>()("").is_err());
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int::<i64>()("-94, Y").unwrap(), (", Y", -94));
+        assert_eq!(int::<i64>()("+94").unwrap(), ("", 94));
+        assert_eq!(int::<i8>()("-128").unwrap(), ("", -128));
+        assert!(matches!(
+            int::<i8>()("-129").unwrap_err().kind,
+            ErrorKind::Invalid(_)
+        ));
+        assert!(int::<i32>()("-").is_err());
+        assert!(int::<i32>()("x1").is_err());
+    }
+
+    #[test]
+    fn test_uint_n() {
+        assert_eq!(uint_n::<i32>(1, 3)("123,").unwrap(), (",", 123));
+        assert_eq!(uint_n::<i32>(1, 3)("12").unwrap(), ("", 12));
+        assert_eq!(uint_n::<i32>(1, 3)("1234").unwrap(), ("4", 123));
+        assert_eq!(
+            uint_n::<i32>(2, 3)("1,").unwrap_err().kind,
+            ErrorKind::MinCount { min: 2, found: 1 }
+        );
+        assert_eq!(int_n::<i32>(1, 2)("-123").unwrap(), ("3", -12));
+    }
+
+    #[test]
+    fn test_hex_bin() {
+        assert_eq!(hex::<u32>()("fF10g").unwrap(), ("g", 0xff10));
+        assert_eq!(bin::<u8>()("1012").unwrap(), ("2", 0b101));
+        assert!(matches!(
+            bin::<u8>()("111111111").unwrap_err().kind,
+            ErrorKind::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(float()("84.25, Y").unwrap(), (", Y", 84.25));
+        assert_eq!(float()("-84.25").unwrap(), ("", -84.25));
+        assert_eq!(float()("42").unwrap(), ("", 42.0));
+        assert_eq!(float()(".5x").unwrap(), ("x", 0.5));
+        assert_eq!(float()("1e-3").unwrap(), ("", 0.001));
+        assert_eq!(float()("2e").unwrap(), ("e", 2.0));
+        assert!(float()("-.").is_err());
+        assert!(float()("abc").is_err());
+    }
+
+    #[test]
+    fn test_preceded_terminated_delimited() {
+        let (p, r) = preceded(lit("Register A: "), uint::<i64>())("Register A: 729\n").unwrap();
+        assert_eq!(r, 729);
+        assert_eq!(p, "\n");
+
+        let (p, r) = terminated(uint::<i64>(), lit("\n"))("42\n43").unwrap();
+        assert_eq!(r, 42);
+        assert_eq!(p, "43");
+
+        let (p, r) = delimited(lit("("), int::<i32>(), lit(")"))("(-7)!").unwrap();
+        assert_eq!(r, -7);
+        assert_eq!(p, "!");
+        assert!(delimited(lit("("), int::<i32>(), lit(")"))("(-7]").is_err());
+    }
+
+    #[test]
+    fn test_sep_by() {
+        let (p, r) = sep_by(uint::<u8>(), lit(","))("0,1,5,4,3,0\n").unwrap();
+        assert_eq!(r, vec![0, 1, 5, 4, 3, 0]);
+        assert_eq!(p, "\n");
+
+        let (p, r) = sep_by(take_min_n_while(1, |c: &char| c.is_alphabetic()), lit(", "))(
+            "r, wr, b, g, bwu, rb, gb, br\n\nbrwrr",
+        )
+        .unwrap();
+        assert_eq!(r, vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+        assert_eq!(p, "\n\nbrwrr");
+
+        // trailing separator is not consumed
+        let (p, r) = sep_by(uint::<u8>(), lit(","))("1,2,").unwrap();
+        assert_eq!(r, vec![1, 2]);
+        assert_eq!(p, ",");
+
+        let (p, r) = sep_by(uint::<u8>(), lit(","))("x").unwrap();
+        assert!(r.is_empty());
+        assert_eq!(p, "x");
+    }
+
+    #[test]
+    fn test_sep_by1() {
+        let (p, r) = sep_by1(uint::<i64>(), lit("\n"))("1\n10\n100\n2024").unwrap();
+        assert_eq!(r, vec![1, 10, 100, 2024]);
+        assert_eq!(p, "");
+
+        assert!(sep_by1(uint::<i64>(), lit("\n"))("x\n1").is_err());
+    }
+
+    #[test]
+    fn test_line_ending() {
+        assert_eq!(line_ending("\nx"), Ok(("x", "\n")));
+        assert_eq!(line_ending("\r\nx"), Ok(("x", "\r\n")));
+        assert_eq!(line_ending(&b"\r\n"[..]), Ok((&b""[..], &b"\r\n"[..])));
+        assert!(line_ending("\rx").is_err());
+        assert!(line_ending("").is_err());
+    }
+
+    #[test]
+    fn test_blocks() {
+        let nums = sep_by1(uint::<u32>(), line_ending);
+        let (p, r) = blocks(&nums)("\n1\n2\n\n\n3\n\n").unwrap();
+        assert_eq!(r, vec![vec![1, 2], vec![3]]);
+        assert_eq!(p, "");
+
+        let (_, r) = blocks(&nums)("1\r\n2\r\n\r\n3\r\n").unwrap();
+        assert_eq!(r, vec![vec![1, 2], vec![3]]);
+
+        let (_, r) = blocks(&nums)("").unwrap();
+        assert!(r.is_empty());
+
+        // lines of only spaces and tabs are blank too
+        let (_, r) = blocks(&nums)("1\n \t\n2\n  \n").unwrap();
+        assert_eq!(r, vec![vec![1], vec![2]]);
+
+        let src = "1\n2\n\n3\nx\n\n4\n";
+        let e = run(blocks(&nums), src).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Trailing);
+        assert_eq!(e.offset(src), 6);
+        assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((4, 2)));
+
+        let src = "1\n\nx";
+        let e = run(blocks(one_of!(lit("1"), lit("2"))), src).unwrap_err();
+        match e.kind {
+            ErrorKind::Alt(es) => assert!(es.iter().all(|e| e.offset(src) == 3)),
+            k => panic!("unexpected {:?}", k),
+        }
+
+        let (_, r) = blocks(bytes::uint::<u8>())(&b"7\n\n8"[..]).unwrap();
+        assert_eq!(r, vec![7, 8]);
+    }
+
+    #[test]
+    fn test_count() {
+        let (p, r) = count(3, terminated(uint::<u8>(), opt(lit(" "))))("1 2 3 4").unwrap();
+        assert_eq!(r, vec![1, 2, 3]);
+        assert_eq!(p, "4");
+
+        assert!(count(3, terminated(uint::<u8>(), opt(lit(" "))))("1 2").is_err());
+    }
+
+    #[test]
+    fn test_tuple() {
+        let button = tuple((
+            preceded(lit("Button "), satisfy(|c: &char| c.is_ascii_uppercase())),
+            preceded(lit(": X+"), int::<i64>()),
+            preceded(lit(", Y+"), int::<i64>()),
+        ));
+        let (p, r) = button("Button A: X+94, Y+34\n").unwrap();
+        assert_eq!(r, ('A', 94, 34));
+        assert_eq!(p, "\n");
+
+        let e = run(&button, "Button A: X+94, Y-34").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected(", Y+".to_string()));
+        assert_eq!(e.pos.unwrap().col, 15);
+    }
+
+    #[test]
+    fn test_alt() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Tok {
+            Num(i32),
+            Word(String),
+            Arrow,
+        }
+
+        let tok = alt((
+            int::<i32>().map(Tok::Num),
+            lit("->").value(Tok::Arrow),
+            take_min_n_while(1, |c: &char| c.is_alphabetic()).map(|w| Tok::Word(w.to_string())),
+        ));
+        assert_eq!(tok("-12 x").unwrap(), (" x", Tok::Num(-12)));
+        assert_eq!(tok("-> x").unwrap(), (" x", Tok::Arrow));
+        assert_eq!(tok("xor y").unwrap(), (" y", Tok::Word("xor".to_string())));
+
+        let e = tok("?").unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Alt(es) if es.len() == 3));
+    }
+
     #[test]
     fn test_seq() {
         let src = "foo,bar,baztest";
@@ -0,0 +1,130 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::{Input, PError, PResult};
+
+/// A cached outcome: bytes consumed and output, or the error.
+type Entry<O> = Result<(usize, O), PError>;
+
+fn lookup<I: Input, O: Clone>(s: I, entry: &Entry<O>) -> PResult<I, O> {
+    match entry {
+        Ok((n, o)) => Ok((s.split_input(*n).1, o.clone())),
+        Err(e) => Err(e.clone()),
+    }
+}
+
+fn entry<I: Input, O: Clone>(s: I, res: &PResult<I, O>) -> Entry<O> {
+    match res {
+        Ok((p, o)) => Ok((s.input_len() - p.input_len(), o.clone())),
+        Err(e) => Err(e.clone()),
+    }
+}
+
+/// Where an input starts in memory and how long it is. Two inputs that are
+/// alive at once only share a key if they are the same slice.
+type Key = (usize, usize);
+
+fn key<I: Input>(s: I) -> Key {
+    (s.input_bytes().as_ptr() as usize, s.input_len())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Rule {
+    Named(&'static str),
+    Anon(usize),
+}
+
+/// Packrat memo table. Results are keyed by rule and by the input slice
+/// itself, so one context can be used for several inputs; `clear` frees the
+/// entries for inputs that are done with.
+///
+/// Left-recursive rules are not supported and will overflow the stack.
+#[derive(Default)]
+pub struct PackratContext {
+    cache: RefCell<HashMap<(Rule, Key), Box<dyn Any>>>,
+    next_id: Cell<usize>,
+    hits: Cell<usize>,
+}
+
+impl PackratContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `op` as the rule `rule` at `s`, or return the cached result if
+    /// the rule has already been tried there. Meant for recursive grammars
+    /// written as functions, where each rule has a fixed name.
+    pub fn apply<I, O, F>(&self, rule: &'static str, s: I, op: F) -> PResult<I, O>
+    where
+        I: Input,
+        O: Clone + 'static,
+        F: FnOnce(I) -> PResult<I, O>,
+    {
+        self.cached(Rule::Named(rule), s, op)
+    }
+
+    /// Wrap `op` so its results are cached in this context.
+    pub fn memo<'c, I, O, F>(&'c self, op: F) -> impl Fn(I) -> PResult<I, O> + 'c
+    where
+        I: Input,
+        O: Clone + 'static,
+        F: Fn(I) -> PResult<I, O> + 'c,
+    {
+        let rule = Rule::Anon(self.next_id.get());
+        self.next_id.set(self.next_id.get() + 1);
+        return move |s| self.cached(rule, s, &op);
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+        self.hits.set(0);
+    }
+
+    fn cached<I, O, F>(&self, rule: Rule, s: I, op: F) -> PResult<I, O>
+    where
+        I: Input,
+        O: Clone + 'static,
+        F: FnOnce(I) -> PResult<I, O>,
+    {
+        let key = (rule, key(s));
+        if let Some(e) = self.cache.borrow().get(&key) {
+            if let Some(e) = e.downcast_ref::<Entry<O>>() {
+                self.hits.set(self.hits.get() + 1);
+                return lookup(s, e);
+            }
+        }
+
+        // the borrow is released while `op` runs, since it may recurse
+        let res = op(s);
+        self.cache
+            .borrow_mut()
+            .insert(key, Box::new(entry(s, &res)));
+        res
+    }
+}
+
+/// Cache the results of `op` by input slice. The cache lives in the returned
+/// parser and is never cleared, so build a new one for each input.
+pub fn memo<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, O>
+where
+    I: Input,
+    O: Clone,
+    F: Fn(I) -> PResult<I, O>,
+{
+    let cache: RefCell<HashMap<Key, Entry<O>>> = RefCell::new(HashMap::new());
+    return move |s| {
+        if let Some(e) = cache.borrow().get(&key(s)) {
+            return lookup(s, e);
+        }
+
+        let res = op(s);
+        cache.borrow_mut().insert(key(s), entry(s, &res));
+        res
+    };
+}
@@ -0,0 +1,141 @@
+use std::num::ParseIntError;
+
+use crate::{ErrorKind, Input, PError, PResult};
+
+/// Integer types the numeric parsers can produce.
+pub trait Integer: Sized {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$t>::from_str_radix(s, radix)
+            }
+        })*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+fn sign_len(s: &[u8]) -> usize {
+    match s.first() {
+        Some(b'+') | Some(b'-') => 1,
+        _ => 0,
+    }
+}
+
+/// Length of the run of at most `max` digits in `radix` at the start of `s`.
+fn digits_len(s: &[u8], radix: u32, max: usize) -> usize {
+    s.iter()
+        .take(max)
+        .take_while(|b| (**b as char).is_digit(radix))
+        .count()
+}
+
+pub(crate) fn radix_int<I, T>(
+    radix: u32,
+    signed: bool,
+    min: usize,
+    max: usize,
+) -> impl Fn(I) -> PResult<I, T>
+where
+    I: Input,
+    T: Integer,
+{
+    return move |s| {
+        let b = s.input_bytes();
+        let sign = if signed { sign_len(b) } else { 0 };
+        let found = digits_len(&b[sign..], radix, max);
+        if found < min.max(1) {
+            return Err(PError::new(
+                ErrorKind::MinCount {
+                    min: min.max(1),
+                    found,
+                },
+                s.split_input(sign).1,
+            ));
+        }
+        // sign and digits are ASCII, so this is valid UTF-8 for either input
+        let n = std::str::from_utf8(&b[..sign + found]).unwrap();
+        let (_, p) = s.split_input(sign + found);
+        match T::from_str_radix(n, radix) {
+            Ok(v) => Ok((p, v)),
+            Err(e) => Err(PError::new(ErrorKind::Invalid(e.to_string()), s)),
+        }
+    };
+}
+
+/// Unsigned decimal integer. Values that do not fit in `T` are an
+/// `ErrorKind::Invalid` error rather than a panic.
+pub fn uint<'p, T: Integer>() -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(10, false, 1, usize::MAX)
+}
+
+/// Decimal integer with an optional leading `+` or `-`.
+pub fn int<'p, T: Integer>() -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(10, true, 1, usize::MAX)
+}
+
+/// Unsigned decimal integer of `min..=max` digits. Scanning stops after `max`
+/// digits, so `uint_n(1, 3)` reads `"1234"` as `123` and leaves `"4"`.
+pub fn uint_n<'p, T: Integer>(min: usize, max: usize) -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(10, false, min, max)
+}
+
+/// Signed decimal integer of `min..=max` digits, not counting the sign.
+pub fn int_n<'p, T: Integer>(min: usize, max: usize) -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(10, true, min, max)
+}
+
+/// Unsigned hexadecimal integer, without a `0x` prefix.
+pub fn hex<'p, T: Integer>() -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(16, false, 1, usize::MAX)
+}
+
+/// Unsigned binary integer, without a `0b` prefix.
+pub fn bin<'p, T: Integer>() -> impl Fn(&'p str) -> PResult<&'p str, T> {
+    radix_int(2, false, 1, usize::MAX)
+}
+
+/// Decimal floating point number: optional sign, digits with an optional
+/// fraction, and an optional exponent, e.g. `-84.25` or `1e-3`.
+pub fn float<'p>() -> impl Fn(&'p str) -> PResult<&'p str, f64> {
+    float_in()
+}
+
+pub(crate) fn float_in<I: Input>() -> impl Fn(I) -> PResult<I, f64> {
+    return move |s| {
+        let b = s.input_bytes();
+        let mut i = sign_len(b);
+        let int_digits = digits_len(&b[i..], 10, usize::MAX);
+        i += int_digits;
+
+        let mut frac_digits = 0;
+        if b.get(i) == Some(&b'.') {
+            frac_digits = digits_len(&b[i + 1..], 10, usize::MAX);
+            if int_digits > 0 || frac_digits > 0 {
+                i += 1 + frac_digits;
+            }
+        }
+        if int_digits + frac_digits == 0 {
+            return Err(PError::new(ErrorKind::MinCount { min: 1, found: 0 }, s));
+        }
+
+        if matches!(b.get(i), Some(b'e') | Some(b'E')) {
+            let sign = sign_len(&b[i + 1..]);
+            let exp_digits = digits_len(&b[i + 1 + sign..], 10, usize::MAX);
+            if exp_digits > 0 {
+                i += 1 + sign + exp_digits;
+            }
+        }
+
+        let n = std::str::from_utf8(&b[..i]).unwrap();
+        let (_, p) = s.split_input(i);
+        match n.parse::<f64>() {
+            Ok(v) => Ok((p, v)),
+            Err(e) => Err(PError::new(ErrorKind::Invalid(e.to_string()), s)),
+        }
+    };
+}
@@ -0,0 +1,219 @@
+use crate::{int, lit, next, sep_by1, take_min_n_while, uint, PResult};
+
+/// Types with a default parser.
+///
+/// `#[derive(Parse)]` builds one from patterns such as
+/// `#[parse("Button A: X+{a.0}, Y+{a.1}")]`: text outside braces is matched
+/// literally and each `{field}` (or `{field.0}` for an element of a tuple
+/// field) is parsed with that type's `Parse` impl. `{{` and `}}` match
+/// literal braces.
+///
+/// The pattern can be put on the struct (or enum variant), where fields it
+/// does not mention are left at their `Default`, or on individual fields,
+/// which are then parsed in order; a field with no pattern is parsed as
+/// `{field}` and `#[parse(skip)]` leaves it at its default. Enum variants
+/// are tried in order, unit variants without a pattern match their own
+/// name, and `#[parse(skip)]` leaves a variant out.
+///
+/// Strings parse as a word of alphanumerics and `_`, and `Vec<T>` as one
+/// or more comma separated `T`s.
+pub trait Parse<'p>: Sized {
+    fn parse(s: &'p str) -> PResult<&'p str, Self>;
+}
+
+macro_rules! impl_parse {
+    ($f:ident: $($t:ty),*) => {
+        $(impl<'p> Parse<'p> for $t {
+            fn parse(s: &'p str) -> PResult<&'p str, Self> {
+                $f::<$t>()(s)
+            }
+        })*
+    };
+}
+
+impl_parse!(int: i8, i16, i32, i64, i128, isize);
+impl_parse!(uint: u8, u16, u32, u64, u128, usize);
+
+impl<'p> Parse<'p> for f64 {
+    fn parse(s: &'p str) -> PResult<&'p str, Self> {
+        crate::float()(s)
+    }
+}
+
+impl<'p> Parse<'p> for char {
+    fn parse(s: &'p str) -> PResult<&'p str, Self> {
+        next(s)
+    }
+}
+
+impl<'p> Parse<'p> for &'p str {
+    fn parse(s: &'p str) -> PResult<&'p str, Self> {
+        take_min_n_while(1, |c: &char| c.is_alphanumeric() || *c == '_')(s)
+    }
+}
+
+impl<'p> Parse<'p> for String {
+    fn parse(s: &'p str) -> PResult<&'p str, Self> {
+        let (p, w) = <&str>::parse(s)?;
+        Ok((p, w.to_string()))
+    }
+}
+
+impl<'p, T: Parse<'p>> Parse<'p> for Vec<T> {
+    fn parse(s: &'p str) -> PResult<&'p str, Self> {
+        sep_by1(T::parse, lit(","))(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(i32::parse("-12,"), Ok((",", -12)));
+        assert!(u8::parse("-1").is_err());
+        assert_eq!(f64::parse("1.5"), Ok(("", 1.5)));
+        assert_eq!(char::parse("ab"), Ok(("b", 'a')));
+        assert_eq!(<&str>::parse("x00 AND"), Ok((" AND", "x00")));
+        assert!(String::parse(" x").is_err());
+        assert_eq!(Vec::<u8>::parse("2,4,1,5\n"), Ok(("\n", vec![2, 4, 1, 5])));
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use crate::{blocks, run, ErrorKind, Parse};
+
+        #[derive(Parse, Debug, PartialEq)]
+        struct Machine {
+            #[parse("Button A: X+{a.0}, Y+{a.1}\n")]
+            a: (i64, i64),
+            #[parse("Button B: X+{b.0}, Y+{b.1}\n")]
+            b: (i64, i64),
+            #[parse("Prize: X={p.0}, Y={p.1}")]
+            p: (i64, i64),
+        }
+
+        #[derive(Parse, Debug, PartialEq)]
+        #[parse("p={p.0},{p.1} v={v.0},{v.1}")]
+        struct Robot {
+            p: (i32, i32),
+            v: (i32, i32),
+        }
+
+        #[derive(Parse, Debug, Default, PartialEq)]
+        #[parse("Register A: {a}\nRegister B: {b}\nRegister C: {c}\n\nProgram: {program}")]
+        struct Program {
+            a: i64,
+            b: i64,
+            c: i64,
+            ip: usize,
+            program: Vec<u8>,
+        }
+
+        #[derive(Parse, Debug, Clone, Copy, PartialEq)]
+        enum Op {
+            #[parse("AND")]
+            And,
+            #[parse("XOR")]
+            Xor,
+            #[parse("OR")]
+            Or,
+            #[parse(skip)]
+            #[allow(dead_code)]
+            Unknown,
+        }
+
+        #[derive(Parse, Debug, PartialEq)]
+        #[parse("{0} {1} {2} -> {3}")]
+        struct Gate<'a>(&'a str, Op, &'a str, &'a str);
+
+        #[derive(Parse, Debug, PartialEq)]
+        enum Cmd {
+            #[parse("mul({0},{1})")]
+            Mul(u32, u32),
+            #[parse("do()")]
+            Do,
+            #[parse("move {{{dx}}}")]
+            Move { dx: i8 },
+        }
+
+        #[derive(Parse, Debug, PartialEq)]
+        struct Pair {
+            #[parse("{x}|")]
+            x: u8,
+            #[parse(skip)]
+            seen: bool,
+            y: u8,
+        }
+
+        #[test]
+        fn test_derive_struct() {
+            let src = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400";
+            let (_, m) = run(Machine::parse, src).unwrap();
+            assert_eq!(
+                m,
+                Machine {
+                    a: (94, 34),
+                    b: (22, 67),
+                    p: (8400, 5400)
+                }
+            );
+
+            let (p, r) = Robot::parse("p=0,4 v=3,-3\n").unwrap();
+            assert_eq!(
+                r,
+                Robot {
+                    p: (0, 4),
+                    v: (3, -3)
+                }
+            );
+            assert_eq!(p, "\n");
+
+            let (_, pgm) = Program::parse(
+                "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0",
+            )
+            .unwrap();
+            assert_eq!(pgm.a, 729);
+            assert_eq!(pgm.ip, 0);
+            assert_eq!(pgm.program, vec![0, 1, 5, 4, 3, 0]);
+
+            assert_eq!(
+                Pair::parse("3|4"),
+                Ok((
+                    "",
+                    Pair {
+                        x: 3,
+                        seen: false,
+                        y: 4
+                    }
+                ))
+            );
+        }
+
+        #[test]
+        fn test_derive_enum() {
+            let (_, g) = Gate::parse("x00 XOR y00 -> z00").unwrap();
+            assert_eq!(g, Gate("x00", Op::Xor, "y00", "z00"));
+            assert_eq!(Op::parse("OR x"), Ok((" x", Op::Or)));
+
+            assert_eq!(Cmd::parse("mul(2,4)"), Ok(("", Cmd::Mul(2, 4))));
+            assert_eq!(Cmd::parse("do()x"), Ok(("x", Cmd::Do)));
+            assert_eq!(Cmd::parse("move {-3}"), Ok(("", Cmd::Move { dx: -3 })));
+
+            let e = Op::parse("NOT").unwrap_err();
+            match e.kind {
+                ErrorKind::Alt(es) => assert_eq!(es.len(), 3),
+                k => panic!("unexpected {:?}", k),
+            }
+        }
+
+        #[test]
+        fn test_derive_errors() {
+            let src = "p=0,4 v=3,-3\n\np=1,x v=0,0\n";
+            let e = run(blocks(Robot::parse), src).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::MinCount { min: 1, found: 0 });
+            assert_eq!(e.pos.map(|p| (p.line, p.col)), Some((3, 5)));
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Display;
+
+use crate::{ErrorKind, Input, PError, PResult};
+
+/// Combinator methods shared by every parser. Implemented for any
+/// `Fn(I) -> PResult<I, O>`, so plain functions and the closures
+/// returned by the free combinators can be chained directly.
+pub trait Parser<I: Input, O> {
+    fn parse(&self, s: I) -> PResult<I, O>;
+
+    /// Transform the output.
+    fn map<B, F>(self, f: F) -> impl Fn(I) -> PResult<I, B>
+    where
+        Self: Sized,
+        F: Fn(O) -> B,
+    {
+        return move |s| {
+            let (p, o) = self.parse(s)?;
+            Ok((p, f(o)))
+        };
+    }
+
+    /// Transform the output with a fallible function. A conversion error
+    /// becomes `ErrorKind::Invalid` at the start of the parsed input.
+    fn map_res<B, E, F>(self, f: F) -> impl Fn(I) -> PResult<I, B>
+    where
+        Self: Sized,
+        F: Fn(O) -> Result<B, E>,
+        E: Display,
+    {
+        return move |s| {
+            let (p, o) = self.parse(s)?;
+            match f(o) {
+                Ok(b) => Ok((p, b)),
+                Err(e) => Err(PError::new(ErrorKind::Invalid(e.to_string()), s)),
+            }
+        };
+    }
+
+    /// Build the next parser from this one's output and run it on the rest.
+    fn and_then<B, P, F>(self, f: F) -> impl Fn(I) -> PResult<I, B>
+    where
+        Self: Sized,
+        P: Parser<I, B>,
+        F: Fn(O) -> P,
+    {
+        return move |s| {
+            let (p, o) = self.parse(s)?;
+            f(o).parse(p)
+        };
+    }
+
+    /// Fail with `ErrorKind::Predicate` unless the output satisfies `predicate`.
+    fn filter<F>(self, predicate: F) -> impl Fn(I) -> PResult<I, O>
+    where
+        Self: Sized,
+        F: Fn(&O) -> bool,
+    {
+        return move |s| {
+            let (p, o) = self.parse(s)?;
+            if predicate(&o) {
+                Ok((p, o))
+            } else {
+                Err(PError::new(ErrorKind::Predicate, s))
+            }
+        };
+    }
+
+    /// Try `other` on the same input if this parser fails.
+    fn or<P>(self, other: P) -> impl Fn(I) -> PResult<I, O>
+    where
+        Self: Sized,
+        P: Parser<I, O>,
+    {
+        return move |s| match self.parse(s) {
+            Ok(v) => Ok(v),
+            Err(e1) => match other.parse(s) {
+                Ok(v) => Ok(v),
+                Err(e2) => Err(PError::new(ErrorKind::Alt(vec![e1, e2]), s)),
+            },
+        };
+    }
+
+    /// Run `other` after this parser and keep both outputs.
+    fn then<B, P>(self, other: P) -> impl Fn(I) -> PResult<I, (O, B)>
+    where
+        Self: Sized,
+        P: Parser<I, B>,
+    {
+        return move |s| {
+            let (p, a) = self.parse(s)?;
+            let (p, b) = other.parse(p)?;
+            Ok((p, (a, b)))
+        };
+    }
+
+    /// Run `other` after this parser and keep only this parser's output.
+    fn skip<B, P>(self, other: P) -> impl Fn(I) -> PResult<I, O>
+    where
+        Self: Sized,
+        P: Parser<I, B>,
+    {
+        return move |s| {
+            let (p, a) = self.parse(s)?;
+            let (p, _) = other.parse(p)?;
+            Ok((p, a))
+        };
+    }
+
+    /// Replace the output with a clone of `v`.
+    fn value<B>(self, v: B) -> impl Fn(I) -> PResult<I, B>
+    where
+        Self: Sized,
+        B: Clone,
+    {
+        return move |s| {
+            let (p, _) = self.parse(s)?;
+            Ok((p, v.clone()))
+        };
+    }
+}
+
+impl<I: Input, O, F> Parser<I, O> for F
+where
+    F: Fn(I) -> PResult<I, O>,
+{
+    fn parse(&self, s: I) -> PResult<I, O> {
+        self(s)
+    }
+}
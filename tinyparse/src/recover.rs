@@ -0,0 +1,117 @@
+use crate::{Input, PError, PResult};
+
+/// Input dropped during recovery, with the error that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped<I> {
+    pub input: I,
+    pub error: PError,
+}
+
+/// Every match found by `scan_for`, plus the runs of input between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scan<I, O> {
+    pub items: Vec<O>,
+    pub skipped: Vec<Skipped<I>>,
+}
+
+fn step<I: Input>(s: I) -> I {
+    s.split_input(s.unit_len()).1
+}
+
+fn consumed<I: Input>(s: I, p: I) -> I {
+    s.split_input(s.input_len() - p.input_len()).0
+}
+
+/// Consume input up to where `sync` matches, or to the end of input. The
+/// sync point itself is not consumed. Never fails.
+pub fn skip_until<I, O, F>(sync: F) -> impl Fn(I) -> PResult<I, I>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    return move |s| {
+        let mut p = s;
+        while p.input_len() > 0 && sync(p).is_err() {
+            p = step(p);
+        }
+        Ok((p, consumed(s, p)))
+    };
+}
+
+/// Run `op`; if it fails, run `recovery` from the same point and return what
+/// it skipped along with `op`'s error. Recovery always consumes at least one
+/// char (or byte), so this can be repeated with `many0` without looping.
+/// Fails with `op`'s error only if `recovery` fails or the input is empty.
+pub fn recover_with<I, O, R, F, RF>(
+    op: F,
+    recovery: RF,
+) -> impl Fn(I) -> PResult<I, Result<O, Skipped<I>>>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+    RF: Fn(I) -> PResult<I, R>,
+{
+    return move |s| match op(s) {
+        Ok((p, o)) => Ok((p, Ok(o))),
+        Err(error) => {
+            let mut p = match recovery(s) {
+                Ok((p, _)) => p,
+                Err(_) => return Err(error),
+            };
+            if p.input_len() == s.input_len() {
+                if s.input_len() == 0 {
+                    return Err(error);
+                }
+                p = step(s);
+            }
+            let input = consumed(s, p);
+            Ok((p, Err(Skipped { input, error })))
+        }
+    };
+}
+
+/// Find every match of `op` in noisy input. `op` is tried at each char (or
+/// byte); input between matches is collected into `Skipped` runs, each with
+/// the error `op` gave at the start of the run. Consumes all input.
+pub fn scan_for<I, O, F>(op: F) -> impl Fn(I) -> PResult<I, Scan<I, O>>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    return move |s| {
+        let mut items: Vec<O> = Vec::new();
+        let mut skipped: Vec<Skipped<I>> = Vec::new();
+        let mut noise: Option<(I, PError)> = None;
+        let mut ss = s;
+
+        while ss.input_len() > 0 {
+            match op(ss) {
+                Ok((p, o)) => {
+                    if let Some((start, error)) = noise.take() {
+                        let input = consumed(start, ss);
+                        skipped.push(Skipped { input, error });
+                    }
+                    items.push(o);
+                    ss = if p.input_len() < ss.input_len() {
+                        p
+                    } else {
+                        step(ss)
+                    };
+                }
+                Err(e) => {
+                    noise.get_or_insert((ss, e));
+                    ss = step(ss);
+                }
+            }
+        }
+
+        if let Some((start, error)) = noise {
+            skipped.push(Skipped {
+                input: start,
+                error,
+            });
+        }
+
+        Ok((ss, Scan { items, skipped }))
+    };
+}
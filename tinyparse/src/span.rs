@@ -0,0 +1,94 @@
+use std::cell::Cell;
+
+use crate::{ErrorKind, Input, PError, PResult, Position};
+
+/// A parsed value with the byte range it came from and the line/column of
+/// its start, all relative to the original input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl<T> Spanned<T> {
+    pub fn map<U, F>(self, f: F) -> Spanned<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        Spanned {
+            value: f(self.value),
+            start: self.start,
+            end: self.end,
+            line: self.line,
+            col: self.col,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Byte offset of `s` in `src`, if `s` is a suffix of it. An empty `s` is
+/// taken to be the end of `src`, wherever it points.
+fn suffix_offset<I: Input>(src: I, s: I) -> Option<usize> {
+    let start = src.input_len().checked_sub(s.input_len())?;
+    let tail = &src.input_bytes()[start..];
+    if s.input_len() == 0 || tail.as_ptr() == s.input_bytes().as_ptr() {
+        Some(start)
+    } else {
+        None
+    }
+}
+
+/// Wrap the output of `op` in a `Spanned`. `src` is the original input that
+/// the parser is eventually run over; positions are computed against it, and
+/// running the parser over anything that is not a suffix of `src` is an
+/// `Invalid` error.
+///
+/// Line and column are counted on from the previous match, so locating
+/// tokens front to back costs one pass over `src` in total.
+pub fn located<I, O, F>(src: I, op: F) -> impl Fn(I) -> PResult<I, Spanned<O>>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    let last = Cell::new(Position::start());
+    return move |s| {
+        let outside = || {
+            PError::new(
+                ErrorKind::Invalid("input is not part of the source".to_string()),
+                s,
+            )
+        };
+        let start = suffix_offset(src, s).ok_or_else(outside)?;
+        let (p, value) = op(s)?;
+        let end = suffix_offset(src, p).ok_or_else(outside)?;
+
+        let mut from = last.get();
+        if from.offset > start {
+            from = Position::start();
+        }
+        let bytes = &src.input_bytes()[from.offset..];
+        let pos = from.advance(Position::from_offset(bytes, start - from.offset));
+        last.set(pos);
+
+        Ok((
+            p,
+            Spanned {
+                value,
+                start,
+                end,
+                line: pos.line,
+                col: pos.col,
+            },
+        ))
+    };
+}
@@ -0,0 +1,346 @@
+use std::io::BufRead;
+
+use crate::{ErrorKind, PError, PResult, Position};
+
+/// Bytes to read before retrying a parser that ran out of input, unless the
+/// pending input is larger.
+const MIN_READ: usize = 8 * 1024;
+
+/// Parse input incrementally from a `BufRead`, keeping only the data the
+/// current parser has not consumed yet.
+///
+/// Parsers see the buffered input as a `&str`. When one fails, or succeeds
+/// having consumed everything buffered, more is read and the parser is run
+/// again, since the buffer may have ended inside an item that some inner
+/// parser stopped short of. Errors are only reported once all input has been
+/// read; a literal still cut short then is reported as `Incomplete`. Outputs
+/// cannot borrow from the buffer, and error positions are relative to the
+/// start of the stream.
+pub struct Stream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    start: usize,
+    pos: Position,
+    eof: bool,
+}
+
+impl<R: BufRead> Stream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            start: 0,
+            pos: Position::start(),
+            eof: false,
+        }
+    }
+
+    /// Position of the next unconsumed byte.
+    pub fn position(&self) -> Position {
+        self.pos
+    }
+
+    /// Whether all input has been read and consumed.
+    pub fn at_end(&mut self) -> Result<bool, PError> {
+        if self.start == self.buf.len() && !self.eof {
+            self.fill()?;
+        }
+        Ok(self.start == self.buf.len() && self.eof)
+    }
+
+    /// Run `op` over the unconsumed input, reading more as needed, and
+    /// consume what it matched.
+    pub fn parse<O, F>(&mut self, op: F) -> Result<O, PError>
+    where
+        F: Fn(&str) -> PResult<&str, O>,
+    {
+        loop {
+            let text = self.text()?;
+            let res = match op(text) {
+                Ok((p, o)) if !p.is_empty() || self.eof => Ok((text.len() - p.len(), o)),
+                Err(mut e) if self.eof => {
+                    if let Some(needed) = needed(&e, text) {
+                        e.kind = ErrorKind::Incomplete { needed };
+                    }
+                    Err(e.locate_at(text, self.pos))
+                }
+                _ => {
+                    self.fill()?;
+                    continue;
+                }
+            };
+
+            let (n, o) = res?;
+            self.consume(n);
+            return Ok(o);
+        }
+    }
+
+    /// Iterate over the items `op` parses, one at a time, until the input
+    /// is exhausted or `op` fails.
+    pub fn iter<O, F>(self, op: F) -> ParseIter<R, F>
+    where
+        F: Fn(&str) -> PResult<&str, O>,
+    {
+        ParseIter {
+            stream: self,
+            op,
+            done: false,
+        }
+    }
+
+    /// The buffered input, up to the last complete UTF-8 char.
+    fn text(&self) -> Result<&str, PError> {
+        let b = &self.buf[self.start..];
+        match std::str::from_utf8(b) {
+            Ok(s) => Ok(s),
+            Err(e) if e.error_len().is_none() => {
+                Ok(std::str::from_utf8(&b[..e.valid_up_to()]).unwrap())
+            }
+            Err(e) => {
+                let mut pos = self.pos;
+                pos.offset += e.valid_up_to();
+                let mut err = PError::new(ErrorKind::Invalid(e.to_string()), "");
+                err.pos = Some(pos);
+                Err(err)
+            }
+        }
+    }
+
+    /// Read at least `MIN_READ` bytes, or as much as is pending, so that
+    /// retrying a long parse does not go quadratic.
+    fn fill(&mut self) -> Result<(), PError> {
+        // drop consumed input before the buffer grows
+        if self.start > 0 && self.start >= self.buf.len() / 2 {
+            self.buf.drain(..self.start);
+            self.start = 0;
+        }
+
+        let want = MIN_READ.max(self.buf.len() - self.start);
+        let mut read = 0;
+        while read < want {
+            let chunk = match self.reader.fill_buf() {
+                Ok(c) => c,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let mut err = PError::new(ErrorKind::Io(e.to_string()), "");
+                    err.pos = Some(self.pos);
+                    return Err(err);
+                }
+            };
+            if chunk.is_empty() {
+                self.eof = true;
+                break;
+            }
+            let n = chunk.len();
+            self.buf.extend_from_slice(chunk);
+            self.reader.consume(n);
+            read += n;
+        }
+        Ok(())
+    }
+
+    fn consume(&mut self, n: usize) {
+        let text = std::str::from_utf8(&self.buf[self.start..self.start + n]).unwrap();
+        self.pos = self.pos.advance(Position::from_offset(text, n));
+        self.start += n;
+    }
+}
+
+/// Bytes missing from the end of `text` for the literal `e` expected, if
+/// the rest of `text` is a proper prefix of it.
+fn needed(e: &PError, text: &str) -> Option<usize> {
+    match &e.kind {
+        ErrorKind::Expected(l) => {
+            let tail = &text[e.offset(text)..];
+            if tail.len() < l.len() && l.starts_with(tail) {
+                Some(l.len() - tail.len())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Iterator over the items parsed from a `Stream`; see `Stream::iter`. It
+/// stops at the end of input, or after yielding the first error.
+pub struct ParseIter<R, F> {
+    stream: Stream<R>,
+    op: F,
+    done: bool,
+}
+
+impl<R, F> ParseIter<R, F> {
+    /// The underlying stream, e.g. to parse a trailer after the items.
+    pub fn into_stream(self) -> Stream<R> {
+        self.stream
+    }
+}
+
+impl<R, O, F> Iterator for ParseIter<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> PResult<&str, O>,
+{
+    type Item = Result<O, PError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = match self.stream.at_end() {
+            Ok(true) => None,
+            Ok(false) => Some(self.stream.parse(&self.op)),
+            Err(e) => Some(Err(e)),
+        };
+        self.done = !matches!(res, Some(Ok(_)));
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+    use crate::{int, line_ending, lit, many1, opt, preceded, sep_by1, terminated, tuple, uint};
+
+    /// A reader that hands out at most `n` bytes per read.
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.1.min(buf.len()).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    fn trickle(src: &str, n: usize) -> BufReader<Trickle<'_>> {
+        BufReader::with_capacity(n, Trickle(src.as_bytes(), n))
+    }
+
+    fn robot(s: &str) -> PResult<&str, (i64, i64, i64, i64)> {
+        let pair = |s| tuple((int::<i64>(), preceded(lit(","), int::<i64>())))(s);
+        let (p, (x, y)) = preceded(lit("p="), pair)(s)?;
+        let (p, (vx, vy)) = preceded(lit(" v="), pair)(p)?;
+        let (p, _) = opt(line_ending)(p)?;
+        Ok((p, (x, y, vx, vy)))
+    }
+
+    fn button(s: &str) -> PResult<&str, usize> {
+        let (p, a) = crate::take_while(|c: &char| *c == 'a')(s)?;
+        let (p, _) = lit("Button")(p)?;
+        Ok((p, a.len()))
+    }
+
+    #[test]
+    fn test_incomplete() {
+        // a literal cut short by the buffer is read on
+        let src = "a".repeat(MIN_READ - 2) + "Bu";
+        let mut s = Stream::new(BufReader::new(src.as_bytes().chain(&b"tton"[..])));
+        assert_eq!(s.parse(button).unwrap(), MIN_READ - 2);
+
+        // and reported as incomplete at the end of input
+        let e = Stream::new("aaBut".as_bytes()).parse(button).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Incomplete { needed: 3 });
+        assert_eq!(e.pos.map(|p| p.offset), Some(2));
+    }
+
+    #[test]
+    fn test_mismatch_at_buffer_end() {
+        // the first read ends on a real mismatch, reported where it is
+        let src = "a".repeat(MIN_READ - 3) + "Bot";
+        let mut s = Stream::new(BufReader::new(src.as_bytes().chain(&b"ton"[..])));
+        let e = s.parse(button).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Expected("Button".to_string()));
+        assert_eq!(e.pos.map(|p| p.offset), Some(MIN_READ - 3));
+    }
+
+    #[test]
+    fn test_boundary_inside_item() {
+        // the first read ends inside "23", so `many1` stops one item early
+        // and the ";" after it fails before the end of the buffer
+        let src = "1 ".repeat(4095) + "23 4 ;";
+        fn items(s: &str) -> PResult<&str, Vec<u32>> {
+            terminated(many1(terminated(uint::<u32>(), lit(" "))), lit(";"))(s)
+        }
+        let mut s = Stream::new(BufReader::with_capacity(MIN_READ, src.as_bytes()));
+        let v = s.parse(items).unwrap();
+        assert_eq!(v.len(), 4097);
+        assert_eq!(v[4095..], [23, 4]);
+    }
+
+    #[test]
+    fn test_stream_parse() {
+        let src = "p=0,4 v=3,-3\np=6,3 v=-1,-3\n";
+        for n in [1, 2, 5, 64] {
+            let mut s = Stream::new(trickle(src, n));
+            assert_eq!(s.parse(robot).unwrap(), (0, 4, 3, -3));
+            assert_eq!(s.position().line, 2);
+            assert_eq!(s.parse(robot).unwrap(), (6, 3, -1, -3));
+            assert!(s.at_end().unwrap());
+        }
+
+        // numbers split across reads are not cut short
+        let mut s = Stream::new(trickle("123456,7", 2));
+        let nums = s.parse(|s| sep_by1(uint::<u32>(), lit(","))(s)).unwrap();
+        assert_eq!(nums, vec![123456, 7]);
+    }
+
+    #[test]
+    fn test_parse_iter() {
+        let src: String = (0..1000)
+            .map(|i| format!("p={},{} v=1,-1\n", i, i * 2))
+            .collect();
+        let robots = Stream::new(trickle(&src, 7)).iter(robot);
+        let mut n = 0;
+        for (i, r) in robots.enumerate() {
+            let (x, y, _, _) = r.unwrap();
+            assert_eq!((x, y), (i as i64, i as i64 * 2));
+            n += 1;
+        }
+        assert_eq!(n, 1000);
+
+        let mut it = Stream::new("".as_bytes()).iter(robot);
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_iter_errors() {
+        let src = "p=0,4 v=3,-3\np=6,x v=-1,-3\np=1,1 v=1,1\n";
+        let mut it = Stream::new(trickle(src, 3)).iter(robot);
+        assert!(it.next().unwrap().is_ok());
+        let e = it.next().unwrap().unwrap_err();
+        assert_eq!(e.pos.map(|p| (p.offset, p.line, p.col)), Some((17, 2, 5)));
+        assert!(it.next().is_none());
+
+        // truncated input
+        let mut s = Stream::new(trickle("p=0,4 v", 2));
+        let e = s.parse(robot).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Incomplete { needed: 1 });
+        assert_eq!(e.pos.map(|p| p.col), Some(6));
+
+        let mut s = Stream::new(&b"p=\xff"[..]);
+        assert!(matches!(
+            s.parse(robot).unwrap_err().kind,
+            ErrorKind::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_stream_multibyte() {
+        // split "é" (two bytes) across reads
+        let mut s = Stream::new(trickle("aé,b\né", 2));
+        fn word(s: &str) -> PResult<&str, String> {
+            let (p, w) = terminated(crate::take_while(|c: &char| *c != ','), lit(","))(s)?;
+            Ok((p, w.to_string()))
+        }
+        let w = s.parse(word).unwrap();
+        assert_eq!(w, "aé");
+        assert_eq!(s.position().col, 4);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Input, PResult};
+
+#[cfg(feature = "trace")]
+use std::cell::{Cell, RefCell};
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static ROOT_LEN: Cell<usize> = const { Cell::new(0) };
+    static CAPTURE: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "trace")]
+fn emit(line: String) {
+    CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(lines) => lines.push(line),
+        None => eprintln!("{}", line),
+    });
+}
+
+/// Log entry and exit of `op` to stderr as an indented tree, with the input
+/// offset, bytes consumed and the outcome. Offsets are relative to the input
+/// of the outermost traced parser. Without the `trace` feature this returns
+/// `op` unchanged. See `trace_capture` to collect the log instead.
+#[cfg(not(feature = "trace"))]
+pub fn trace<I, O, F>(name: &'static str, op: F) -> impl Fn(I) -> PResult<I, O>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    let _ = name;
+    op
+}
+
+#[cfg(feature = "trace")]
+pub fn trace<I, O, F>(name: &'static str, op: F) -> impl Fn(I) -> PResult<I, O>
+where
+    I: Input,
+    F: Fn(I) -> PResult<I, O>,
+{
+    return move |s| {
+        let depth = DEPTH.with(|d| d.get());
+        if depth == 0 {
+            ROOT_LEN.with(|r| r.set(s.input_len()));
+        }
+        let offset = ROOT_LEN.with(|r| r.get()).saturating_sub(s.input_len());
+        let indent = "  ".repeat(depth);
+
+        emit(format!("{}> {} @{}", indent, name, offset));
+        DEPTH.with(|d| d.set(depth + 1));
+        let res = op(s);
+        DEPTH.with(|d| d.set(depth));
+
+        emit(match &res {
+            Ok((p, _)) => format!(
+                "{}< {} @{} ok +{}",
+                indent,
+                name,
+                offset,
+                s.input_len() - p.input_len()
+            ),
+            Err(e) => format!("{}< {} @{} err: {}", indent, name, offset, e),
+        });
+        res
+    };
+}
+
+/// Current nesting depth of traced parsers; always 0 without the feature.
+pub fn trace_depth() -> usize {
+    #[cfg(feature = "trace")]
+    return DEPTH.with(|d| d.get());
+    #[cfg(not(feature = "trace"))]
+    return 0;
+}
+
+/// Run `f`, collecting the lines `trace` logs on this thread instead of
+/// printing them. The lines are always empty without the feature.
+pub fn trace_capture<R, F>(f: F) -> (R, Vec<String>)
+where
+    F: FnOnce() -> R,
+{
+    #[cfg(feature = "trace")]
+    {
+        let prev = CAPTURE.with(|c| c.replace(Some(Vec::new())));
+        let r = f();
+        let lines = CAPTURE.with(|c| c.replace(prev)).unwrap_or_default();
+        return (r, lines);
+    }
+    #[cfg(not(feature = "trace"))]
+    return (f(), Vec::new());
+}
@@ -0,0 +1,71 @@
+use crate::{ErrorKind, Input, PError, PResult, Parser};
+
+/// A tuple of parsers run in sequence, producing the tuple of their outputs.
+pub trait Tuple<I, O> {
+    fn parse_tuple(&self, s: I) -> PResult<I, O>;
+}
+
+/// A tuple of parsers with a shared output type, tried in order.
+pub trait Alt<I, O> {
+    fn parse_alt(&self, s: I) -> PResult<I, O>;
+}
+
+macro_rules! impl_tuple {
+    ($($P:ident $O:ident $o:ident $i:tt),+) => {
+        impl<I: Input, $($P, $O),+> Tuple<I, ($($O,)+)> for ($($P,)+)
+        where
+            $($P: Parser<I, $O>),+
+        {
+            fn parse_tuple(&self, s: I) -> PResult<I, ($($O,)+)> {
+                $( let (s, $o) = self.$i.parse(s)?; )+
+                Ok((s, ($($o,)+)))
+            }
+        }
+
+        impl<I: Input, O, $($P),+> Alt<I, O> for ($($P,)+)
+        where
+            $($P: Parser<I, O>),+
+        {
+            fn parse_alt(&self, s: I) -> PResult<I, O> {
+                let mut errs = Vec::new();
+                $(
+                    match self.$i.parse(s) {
+                        Ok(v) => return Ok(v),
+                        Err(e) => errs.push(e),
+                    }
+                )+
+                Err(PError::new(ErrorKind::Alt(errs), s))
+            }
+        }
+    };
+}
+
+impl_tuple!(P0 O0 o0 0);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4, P5 O5 o5 5);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4, P5 O5 o5 5, P6 O6 o6 6);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4, P5 O5 o5 5, P6 O6 o6 6, P7 O7 o7 7);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4, P5 O5 o5 5, P6 O6 o6 6, P7 O7 o7 7, P8 O8 o8 8);
+impl_tuple!(P0 O0 o0 0, P1 O1 o1 1, P2 O2 o2 2, P3 O3 o3 3, P4 O4 o4 4, P5 O5 o5 5, P6 O6 o6 6, P7 O7 o7 7, P8 O8 o8 8, P9 O9 o9 9);
+
+/// Run every parser of the tuple in sequence and return all outputs.
+pub fn tuple<I, O, T>(parsers: T) -> impl Fn(I) -> PResult<I, O>
+where
+    I: Input,
+    T: Tuple<I, O>,
+{
+    return move |s| parsers.parse_tuple(s);
+}
+
+/// Return the output of the first parser in the tuple that succeeds. Unlike
+/// `one_of`, the parsers may be different closure types.
+pub fn alt<I, O, T>(parsers: T) -> impl Fn(I) -> PResult<I, O>
+where
+    I: Input,
+    T: Alt<I, O>,
+{
+    return move |s| parsers.parse_alt(s);
+}
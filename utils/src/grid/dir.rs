@@ -0,0 +1,208 @@
+use std::ops::{Add, AddAssign};
+
+use super::Point;
+
+/// One of the four orthogonal directions, in clockwise order from `Up`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn all() -> impl Iterator<Item = Dir4> {
+        Self::ALL.into_iter()
+    }
+
+    /// Offset of one step; `Up` is towards row 0.
+    pub fn delta(self) -> Point {
+        match self {
+            Dir4::Up => Point::new(0, -1),
+            Dir4::Right => Point::new(1, 0),
+            Dir4::Down => Point::new(0, 1),
+            Dir4::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Parse an arrow: `^`, `>`, `v` or `<`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Dir4::Up),
+            '>' => Some(Dir4::Right),
+            'v' => Some(Dir4::Down),
+            '<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Dir4::Up => '^',
+            Dir4::Right => '>',
+            Dir4::Down => 'v',
+            Dir4::Left => '<',
+        }
+    }
+}
+
+/// One of the eight compass directions, in clockwise order from `Up`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    pub fn all() -> impl Iterator<Item = Dir8> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Dir8::Up => Point::new(0, -1),
+            Dir8::UpRight => Point::new(1, -1),
+            Dir8::Right => Point::new(1, 0),
+            Dir8::DownRight => Point::new(1, 1),
+            Dir8::Down => Point::new(0, 1),
+            Dir8::DownLeft => Point::new(-1, 1),
+            Dir8::Left => Point::new(-1, 0),
+            Dir8::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// Turn an eighth of a circle clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Parse an arrow as an orthogonal direction; see `Dir4::from_char`.
+    pub fn from_char(c: char) -> Option<Self> {
+        Dir4::from_char(c).map(Self::from)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Self {
+        Self::ALL[d as usize * 2]
+    }
+}
+
+impl Add<Dir4> for Point {
+    type Output = Point;
+
+    fn add(self, d: Dir4) -> Point {
+        self + d.delta()
+    }
+}
+
+impl Add<Dir8> for Point {
+    type Output = Point;
+
+    fn add(self, d: Dir8) -> Point {
+        self + d.delta()
+    }
+}
+
+impl AddAssign<Dir4> for Point {
+    fn add_assign(&mut self, d: Dir4) {
+        *self = *self + d;
+    }
+}
+
+impl AddAssign<Dir8> for Point {
+    fn add_assign(&mut self, d: Dir8) {
+        *self = *self + d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir4() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+        assert_eq!(Dir4::Down.opposite(), Dir4::Up);
+
+        for d in Dir4::all() {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.delta().rotate_right(), d.turn_right().delta());
+            assert_eq!(-d.delta(), d.opposite().delta());
+            assert_eq!(Dir4::from_char(d.to_char()), Some(d));
+        }
+        assert_eq!(Dir4::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_dir8() {
+        assert_eq!(Dir8::all().count(), 8);
+        assert_eq!(Dir8::Up.turn_right(), Dir8::UpRight);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::UpLeft);
+        assert_eq!(Dir8::UpRight.opposite(), Dir8::DownLeft);
+        assert_eq!(Dir8::from(Dir4::Left), Dir8::Left);
+        assert_eq!(Dir8::from_char('v'), Some(Dir8::Down));
+
+        for d in Dir8::all() {
+            assert_eq!(-d.delta(), d.opposite().delta());
+            assert_eq!(d.is_diagonal(), d.delta().x != 0 && d.delta().y != 0);
+            assert_eq!(d.delta().chebyshev(Point::ORIGIN), 1);
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let mut p = Point::new(2, 2);
+        assert_eq!(p + Dir4::Up, Point::new(2, 1));
+        assert_eq!(p + Dir8::DownLeft, Point::new(1, 3));
+        p += Dir4::Right;
+        p += Dir8::UpRight;
+        assert_eq!(p, Point::new(4, 1));
+    }
+}
@@ -1,8 +1,36 @@
 use core::iter::StepBy;
 use core::slice::{Iter, IterMut};
+use std::convert::Infallible;
 use std::fmt;
 use std::marker::Copy;
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+mod dir;
+mod point;
+pub mod search;
+
+pub use dir::{Dir4, Dir8};
+pub use point::Point;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GridError {
+    #[error("row {row} has {found} cells, expected {expected}")]
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("invalid cell {c:?} at ({x},{y}): {message}")]
+    Cell {
+        x: usize,
+        y: usize,
+        c: char,
+        message: String,
+    },
+}
 
 #[derive(Clone)]
 pub struct Grid<T> {
@@ -25,6 +53,45 @@ where
         }
     }
 
+    /// Build a grid from lines of text, converting each char with `f`.
+    /// Trailing blank lines are ignored, and every row must be as wide as
+    /// the first.
+    pub fn parse_with<E, F>(src: &str, f: F) -> Result<Self, GridError>
+    where
+        E: fmt::Display,
+        F: Fn(char) -> Result<T, E>,
+    {
+        let rows: Vec<&str> = src.trim_end_matches(['\n', '\r']).lines().collect();
+        let width = rows.first().map_or(0, |r| r.chars().count());
+        let mut g: Vec<T> = Vec::with_capacity(width * rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            let found = row.chars().count();
+            if found != width {
+                return Err(GridError::Ragged {
+                    row: y,
+                    expected: width,
+                    found,
+                });
+            }
+            for (x, c) in row.chars().enumerate() {
+                let v = f(c).map_err(|e| GridError::Cell {
+                    x,
+                    y,
+                    c,
+                    message: e.to_string(),
+                })?;
+                g.push(v);
+            }
+        }
+
+        Ok(Self {
+            width: width as i32,
+            height: rows.len() as i32,
+            g,
+        })
+    }
+
     pub fn from_vec(v: &Vec<T>, w: i32, h: i32) -> Self {
         Self {
             width: w,
@@ -65,6 +132,60 @@ where
         }
     }
 
+    pub fn contains(&self, p: Point) -> bool {
+        self.in_bounds(p.x, p.y)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.at(p.x, p.y)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(self.at_mut(p.x, p.y))
+        } else {
+            None
+        }
+    }
+
+    /// The in-bounds orthogonal neighbors of `p`, clockwise from up.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        Dir4::all().filter_map(move |d| self.get(p + d).map(|v| (p + d, v)))
+    }
+
+    /// The in-bounds neighbors of `p`, diagonals included, clockwise from up.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        Dir8::all().filter_map(move |d| self.get(p + d).map(|v| (p + d, v)))
+    }
+
+    /// The orthogonal neighbors of `p` whose value matches `pred`.
+    pub fn neighbors_where<'a, F>(
+        &'a self,
+        p: Point,
+        pred: F,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        self.neighbors4(p).filter(move |(_, v)| pred(v))
+    }
+
+    /// The orthogonal neighbors of `p` on a torus, where stepping off one
+    /// edge enters from the opposite one. Yields four neighbors, or none if
+    /// the grid is empty.
+    pub fn neighbors_wrapping(&self, p: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        let n = if self.width == 0 || self.height == 0 {
+            0
+        } else {
+            4
+        };
+        Dir4::all().take(n).map(move |d| {
+            let q = p + d;
+            let q = Point::new(q.x.rem_euclid(self.width), q.y.rem_euclid(self.height));
+            (q, &self[q])
+        })
+    }
+
     pub fn at_mut(&mut self, x: i32, y: i32) -> &mut T {
         assert!(x < self.width && y < self.height);
         let i: usize = (x + y * self.width) as usize;
@@ -176,6 +297,21 @@ where
     }
 }
 
+impl Grid<char> {
+    /// Build a grid of the chars in `src`; see `parse_with`.
+    pub fn parse(src: &str) -> Result<Self, GridError> {
+        Self::parse_with(src, Ok::<char, Infallible>)
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl<T> fmt::Display for Grid<T>
 where
     T: Clone + Copy + fmt::Display + PartialEq<T>,
@@ -298,6 +434,38 @@ where
     }
 }
 
+impl<T> Index<Point> for Grid<T>
+where
+    T: Clone + Copy + PartialEq<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point) -> &T {
+        match self.get(p) {
+            Some(v) => v,
+            None => panic!(
+                "out of bound: ({p} out of ({},{}))",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T>
+where
+    T: Clone + Copy + PartialEq<T>,
+{
+    #[inline]
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (w, h) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(v) => v,
+            None => panic!("out of bound: ({p} out of ({w},{h}))"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,6 +549,69 @@ mod tests {
         assert_eq!(6, grid[(1, 1)]);
     }
 
+    #[test]
+    fn test_index_point() {
+        let mut grid = Grid::parse("abc\ndef").unwrap();
+        let p = Point::new(2, 1);
+
+        assert_eq!(grid[p], 'f');
+        assert_eq!(grid.get(p), Some(&'f'));
+        assert_eq!(grid.get(Point::new(3, 1)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+
+        grid[p] = 'z';
+        *grid.get_mut(Point::new(0, 0)).unwrap() = 'y';
+        assert_eq!(grid.get_mut(Point::new(0, 2)), None);
+        assert_eq!(grid.to_string(), "ybc\ndez\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_point_out_of_bounds() {
+        let grid = Grid::parse("ab").unwrap();
+        let _ = grid[Point::new(0, 1)];
+    }
+
+    fn vals<'a>(it: impl Iterator<Item = (Point, &'a char)>) -> String {
+        it.map(|(_, v)| *v).collect()
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::parse("abc\ndef\nghi").unwrap();
+
+        assert_eq!(vals(grid.neighbors4(Point::new(1, 1))), "bfhd");
+        assert_eq!(vals(grid.neighbors4(Point::new(0, 0))), "bd");
+        assert_eq!(vals(grid.neighbors8(Point::new(1, 1))), "bcfihgda");
+        assert_eq!(vals(grid.neighbors8(Point::new(2, 2))), "fhe");
+
+        let ps: Vec<Point> = grid.neighbors4(Point::new(2, 0)).map(|(p, _)| p).collect();
+        assert_eq!(ps, vec![Point::new(2, 1), Point::new(1, 0)]);
+
+        let vowels = "aeiou".to_string();
+        let p = Point::new(1, 0);
+        assert_eq!(vals(grid.neighbors_where(p, |c| vowels.contains(*c))), "ea");
+        assert_eq!(vals(grid.neighbors_where(p, |c| *c > grid[p])), "ce");
+    }
+
+    #[test]
+    fn test_neighbors_wrapping() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        let ns: Vec<(Point, &char)> = grid.neighbors_wrapping(Point::new(0, 0)).collect();
+        assert_eq!(
+            ns,
+            vec![
+                (Point::new(0, 1), &'d'),
+                (Point::new(1, 0), &'b'),
+                (Point::new(0, 1), &'d'),
+                (Point::new(2, 0), &'c'),
+            ]
+        );
+
+        let empty = Grid::parse("").unwrap();
+        assert_eq!(empty.neighbors_wrapping(Point::new(0, 0)).count(), 0);
+    }
+
     #[test]
     fn test_find() {
         let g = vec![vec![1, 2, 3], vec![5, 6, 7], vec![9, 10, 11]];
@@ -389,6 +620,39 @@ mod tests {
         assert_eq!(Some((1, 1)), grid.find(&6));
     }
 
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("#.@\n.##\n\n").unwrap();
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid[(2, 0)], '@');
+
+        let grid: Grid<char> = "ab\r\ncd\r\n".parse().unwrap();
+        assert_eq!(grid.row(1).collect::<String>(), "cd");
+
+        let grid: Grid<char> = "".parse().unwrap();
+        assert_eq!(grid.size(), 0);
+
+        assert_eq!(
+            Grid::parse("...\n..\n...").err(),
+            Some(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let grid = Grid::parse_with("0123\n4567", |c| c.to_digit(10).ok_or("not a digit")).unwrap();
+        assert_eq!(grid[(1, 1)], 5);
+
+        let e = Grid::parse_with("01\n2.", |c| c.to_digit(10).ok_or("not a digit"))
+            .err()
+            .unwrap();
+        assert_eq!(e.to_string(), "invalid cell '.' at (1,1): not a digit");
+    }
+
     #[test]
     fn test_empty_fill() {
         let mut g: Grid<char> = Grid::empty(3, 3);
@@ -0,0 +1,145 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A grid coordinate or offset. `y` grows downwards, matching row order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(self, other: Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Rotate a quarter turn clockwise about the origin, as seen on screen.
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotate a quarter turn counter-clockwise about the origin.
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(p: Point) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, o: Point) -> Point {
+        Point::new(self.x + o.x, self.y + o.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, o: Point) -> Point {
+        Point::new(self.x - o.x, self.y - o.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, k: i32) -> Point {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, o: Point) {
+        *self = *self + o;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, o: Point) {
+        *self = *self - o;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arith() {
+        let a = Point::new(4, 3);
+        let b = Point::new(5, 5);
+        assert_eq!(a + b, Point::new(9, 8));
+        assert_eq!(a - b, Point::new(-1, -2));
+        assert_eq!((b - a) * 3, Point::new(3, 6));
+        assert_eq!(-a, Point::new(-4, -3));
+
+        let mut c = a;
+        c += b;
+        c -= Point::new(1, 1);
+        assert_eq!(c, Point::new(8, 7));
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Point::new(1, -2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!(a.manhattan(a), 0);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let up = Point::new(0, -1);
+        assert_eq!(up.rotate_right(), Point::new(1, 0));
+        assert_eq!(up.rotate_left(), Point::new(-1, 0));
+        assert_eq!(up.rotate_right().rotate_right(), -up);
+        assert_eq!(
+            Point::new(2, 1).rotate_left().rotate_right(),
+            Point::new(2, 1)
+        );
+    }
+
+    #[test]
+    fn test_tuples() {
+        let p: Point = (3, -1).into();
+        assert_eq!(p, Point::new(3, -1));
+        assert_eq!(<(i32, i32)>::from(p), (3, -1));
+    }
+}
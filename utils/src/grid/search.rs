@@ -0,0 +1,229 @@
+//! Breadth-first searches over a `Grid`, moving between orthogonal
+//! neighbors. Visited cells are tracked in a grid the size of the input
+//! rather than a hash set.
+
+use std::collections::VecDeque;
+
+use super::{Grid, Point};
+
+/// A grid of the same size as `g`, with every cell set to `v`.
+fn filled<T, U>(g: &Grid<T>, v: U) -> Grid<U>
+where
+    U: Copy + PartialEq,
+{
+    let mut r = Grid::empty(g.width, g.height);
+    r.fill(&v);
+    r
+}
+
+/// Step counts from the start of a `bfs`.
+#[derive(Clone)]
+pub struct DistanceMap {
+    dist: Grid<Option<u32>>,
+}
+
+impl DistanceMap {
+    /// Steps to reach `p`, or `None` if it was not reached.
+    pub fn get(&self, p: Point) -> Option<u32> {
+        self.dist.get(p).copied().flatten()
+    }
+
+    pub fn reached(&self) -> impl Iterator<Item = (Point, u32)> + '_ {
+        self.dist
+            .iter_points()
+            .map(Point::from)
+            .filter_map(move |p| self.get(p).map(|d| (p, d)))
+    }
+
+    /// The reached cell furthest from the start, and its distance.
+    pub fn farthest(&self) -> Option<(Point, u32)> {
+        self.reached().max_by_key(|(_, d)| *d)
+    }
+}
+
+/// Distances from `start` to every cell reachable from it. A step from one
+/// cell to its neighbor is allowed when `passable(from, to)` holds.
+pub fn bfs<T, F>(grid: &Grid<T>, start: Point, passable: F) -> DistanceMap
+where
+    T: Copy + PartialEq,
+    F: Fn(&T, &T) -> bool,
+{
+    bfs_multi(grid, [start], passable)
+}
+
+/// Like `bfs`, but each cell's distance is to the nearest of `starts`.
+pub fn bfs_multi<T, I, F>(grid: &Grid<T>, starts: I, passable: F) -> DistanceMap
+where
+    T: Copy + PartialEq,
+    I: IntoIterator<Item = Point>,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut dist: Grid<Option<u32>> = filled(grid, None);
+    let mut q: VecDeque<Point> = VecDeque::new();
+
+    for s in starts {
+        if let Some(d @ None) = dist.get_mut(s) {
+            *d = Some(0);
+            q.push_back(s);
+        }
+    }
+
+    while let Some(p) = q.pop_front() {
+        let d = dist[p].unwrap_or_default();
+        for (np, v) in grid.neighbors4(p) {
+            if dist[np].is_none() && passable(&grid[p], v) {
+                dist[np] = Some(d + 1);
+                q.push_back(np);
+            }
+        }
+    }
+
+    DistanceMap { dist }
+}
+
+fn fill_from<T, F>(
+    grid: &Grid<T>,
+    start: Point,
+    same_region: &F,
+    seen: &mut Grid<bool>,
+) -> Vec<Point>
+where
+    T: Copy + PartialEq,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut region = Vec::new();
+    match seen.get_mut(start) {
+        Some(s @ false) => *s = true,
+        _ => return region,
+    }
+
+    let mut q: VecDeque<Point> = VecDeque::from([start]);
+    while let Some(p) = q.pop_front() {
+        region.push(p);
+        for (np, v) in grid.neighbors4(p) {
+            if !seen[np] && same_region(&grid[p], v) {
+                seen[np] = true;
+                q.push_back(np);
+            }
+        }
+    }
+    region
+}
+
+/// The cells connected to `start` through neighbors for which
+/// `same_region(from, to)` holds, in breadth-first order.
+pub fn flood_fill<T, F>(grid: &Grid<T>, start: Point, same_region: F) -> Vec<Point>
+where
+    T: Copy + PartialEq,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut seen = filled(grid, false);
+    fill_from(grid, start, &same_region, &mut seen)
+}
+
+/// A partition of a grid into connected regions.
+#[derive(Clone)]
+pub struct Components {
+    /// Region id of each cell, numbered in the order regions are found when
+    /// scanning rows top to bottom.
+    pub ids: Grid<usize>,
+    /// Number of cells in each region, indexed by id.
+    pub sizes: Vec<usize>,
+}
+
+impl Components {
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+}
+
+/// Split the grid into regions of neighboring cells for which `eq` holds.
+pub fn components<T, F>(grid: &Grid<T>, eq: F) -> Components
+where
+    T: Copy + PartialEq,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut seen = filled(grid, false);
+    let mut ids = filled(grid, 0);
+    let mut sizes = Vec::new();
+
+    for p in grid.iter_points().map(Point::from) {
+        let region = fill_from(grid, p, &eq, &mut seen);
+        if region.is_empty() {
+            continue;
+        }
+        for q in &region {
+            ids[*q] = sizes.len();
+        }
+        sizes.push(region.len());
+    }
+
+    Components { ids, sizes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.
+.##.
+....
+#..E";
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse(MAZE).unwrap();
+        let d = bfs(&grid, Point::new(0, 0), |_, c| *c != '#');
+
+        assert_eq!(d.get(Point::new(0, 0)), Some(0));
+        assert_eq!(d.get(Point::new(3, 3)), Some(6));
+        assert_eq!(d.get(Point::new(3, 0)), Some(7));
+        assert_eq!(d.get(Point::new(2, 0)), None);
+        assert_eq!(d.get(Point::new(9, 9)), None);
+        assert_eq!(d.reached().count(), 12);
+        assert_eq!(d.farthest(), Some((Point::new(3, 0), 7)));
+
+        // steps that depend on both ends: climb by exactly one
+        let hills = Grid::parse_with("0123\n1994\n9765", |c| c.to_digit(10).ok_or("")).unwrap();
+        let d = bfs(&hills, Point::new(0, 0), |a, b| *b == *a + 1);
+        assert_eq!(d.get(Point::new(1, 2)), Some(7));
+        assert_eq!(d.get(Point::new(1, 1)), None);
+    }
+
+    #[test]
+    fn test_bfs_multi() {
+        let grid = Grid::parse(MAZE).unwrap();
+        let starts = [Point::new(0, 0), Point::new(3, 3)];
+        let d = bfs_multi(&grid, starts, |_, c| *c != '#');
+
+        assert_eq!(d.get(Point::new(3, 0)), Some(3));
+        assert_eq!(d.get(Point::new(0, 2)), Some(2));
+        assert_eq!(d.farthest().map(|(_, n)| n), Some(3));
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Grid::parse("AAB\nABB\nAAB").unwrap();
+        let a = flood_fill(&grid, Point::new(0, 0), |x, y| x == y);
+        assert_eq!(a.len(), 5);
+        assert_eq!(a[0], Point::new(0, 0));
+        assert!(a.iter().all(|p| grid[*p] == 'A'));
+
+        assert!(flood_fill(&grid, Point::new(-1, 0), |x, y| x == y).is_empty());
+    }
+
+    #[test]
+    fn test_components() {
+        let grid = Grid::parse("AAB\nCAB\nAAC").unwrap();
+        let c = components(&grid, |x, y| x == y);
+
+        assert_eq!(c.sizes, vec![5, 2, 1, 1]);
+        assert_eq!(c.len(), 4);
+        assert_eq!(c.ids.to_string(), "001\n201\n003\n");
+    }
+}
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SectionError {
+    #[error("expected {expected} sections, found {found}")]
+    Count { expected: usize, found: usize },
+    #[error("failed to parse section {index}: {message}")]
+    Parse { index: usize, message: String },
+}
+
+/// Split puzzle input into sections separated by one or more blank lines,
+/// which may hold spaces and tabs. Leading and trailing blank lines are
+/// ignored and `\r\n` is normalized to `\n`; sections are only copied when
+/// they contain a `\r`.
+pub fn sections(src: &str) -> Vec<Cow<'_, str>> {
+    tinyparse::block_ranges(src)
+        .into_iter()
+        .map(|(s, e)| {
+            let sec = &src[s..e];
+            if sec.contains('\r') {
+                Cow::Owned(sec.replace("\r\n", "\n"))
+            } else {
+                Cow::Borrowed(sec)
+            }
+        })
+        .collect()
+}
+
+/// Like `sections`, but for input with exactly `N` sections, so the result
+/// can be destructured: `let [rules, updates] = sections_n(src)?;`.
+pub fn sections_n<const N: usize>(src: &str) -> Result<[Cow<'_, str>; N], SectionError> {
+    let secs = sections(src);
+    let found = secs.len();
+    secs.try_into()
+        .map_err(|_| SectionError::Count { expected: N, found })
+}
+
+/// Parse every section with `f`, reporting the index of the first section
+/// that fails.
+pub fn parse_sections<T, E, F>(src: &str, mut f: F) -> Result<Vec<T>, SectionError>
+where
+    F: FnMut(&str) -> Result<T, E>,
+    E: Display,
+{
+    sections(src)
+        .iter()
+        .enumerate()
+        .map(|(index, s)| {
+            f(s).map_err(|e| SectionError::Parse {
+                index,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let src = "47|53\n97|13\n\n75,47,61\n97,61,53\n";
+        assert_eq!(sections(src), vec!["47|53\n97|13", "75,47,61\n97,61,53"]);
+
+        let src = "\n\na\nb\n\n\n\nc\n\n\n";
+        assert_eq!(sections(src), vec!["a\nb", "c"]);
+
+        let src = "a\r\nb\r\n\r\nc\r\n";
+        let secs = sections(src);
+        assert_eq!(secs, vec!["a\nb", "c"]);
+        assert!(matches!(secs[0], Cow::Owned(_)));
+        assert!(matches!(secs[1], Cow::Borrowed(_)));
+
+        assert!(sections("").is_empty());
+        assert_eq!(sections("\n \n").len(), 0);
+        assert_eq!(sections("a\n \t\nb\n").len(), 2);
+    }
+
+    #[test]
+    fn test_sections_n() {
+        let [regs, prog] = sections_n("Register A: 729\n\nProgram: 0,1,5\n").unwrap();
+        assert_eq!(regs, "Register A: 729");
+        assert_eq!(prog, "Program: 0,1,5");
+
+        let e = sections_n::<2>("a\n\nb\n\nc").unwrap_err();
+        assert!(matches!(
+            e,
+            SectionError::Count {
+                expected: 2,
+                found: 3
+            }
+        ));
+        assert_eq!(e.to_string(), "expected 2 sections, found 3");
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let src = "1\n2\n\n3\n\n4\nx\n";
+        let sum = |s: &str| s.lines().map(|l| l.parse::<i32>()).sum::<Result<i32, _>>();
+
+        assert_eq!(parse_sections("1\n2\n\n3", sum).unwrap(), vec![3, 3]);
+
+        let e = parse_sections(src, sum).unwrap_err();
+        assert!(matches!(e, SectionError::Parse { index: 2, .. }));
+        assert!(e.to_string().starts_with("failed to parse section 2: "));
+    }
+}
@@ -8,6 +8,7 @@ use itertools::traits::HomogeneousTuple;
 use itertools::Itertools;
 
 pub mod grid;
+pub mod input;
 
 pub fn read_lines(f: &str) -> Result<Vec<String>, std::io::Error> {
     let file = File::open(f).expect("File does not exist");
@@ -17,6 +18,9 @@ pub fn read_lines(f: &str) -> Result<Vec<String>, std::io::Error> {
         .collect::<Result<Vec<String>, std::io::Error>>()
 }
 
+#[deprecated(
+    note = "use `tinyparse::scan!` or `tinyparse::from_lines`, which match the text around each number"
+)]
 pub fn nums<T>(l: &str) -> Vec<T>
 where
     T: FromStr,
@@ -119,6 +123,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_nums() {
         let s = "Button A: X+94, Y+34";
         let expected: [i64; 2] = [94, 34];